@@ -0,0 +1,89 @@
+//! Hashes the sources of every day, so the answer cache can tell when a day
+//! was rebuilt with different code.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Shared crates the days are built from: a change to any of them may change
+/// every answer.
+const LIBS: &str = "libs";
+const DAYS: &str = "days";
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/days.rs");
+    let libs = hash_crates(Path::new(LIBS));
+
+    let mut days = Vec::new();
+    for dir in sorted_entries(Path::new(DAYS)) {
+        let day = dir
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day_")?.parse::<u8>().ok());
+        if let Some(day) = day {
+            days.push((day, hash_crate(&dir, libs)));
+        }
+    }
+
+    let entries = days
+        .iter()
+        .map(|(day, hash)| format!("    ({day}, {hash:#018x}),\n"))
+        .collect::<String>();
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(
+        out_dir.join("build_hashes.rs"),
+        format!("/// Hash of the sources of each day and the libs.\nconst BUILD_HASHES: &[(u8, u64)] = &[\n{entries}];\n"),
+    )
+    .expect("failed to write build_hashes.rs");
+}
+
+fn hash_crates(dir: &Path) -> u64 {
+    sorted_entries(dir)
+        .iter()
+        .fold(FNV_OFFSET, |hash, dir| hash_crate(dir, hash))
+}
+
+/// Folds the manifest and every source file of the crate in `dir` into
+/// `hash`, also asking cargo to rerun this script when they change.
+fn hash_crate(dir: &Path, hash: u64) -> u64 {
+    let mut files = vec![dir.join("Cargo.toml")];
+    collect_files(&dir.join("src"), &mut files);
+    let mut hash = hash;
+    for file in files {
+        println!("cargo:rerun-if-changed={}", file.display());
+        let content = fs::read(&file).unwrap_or_default();
+        hash = fnv1a(hash, file.to_string_lossy().as_bytes());
+        hash = fnv1a(hash, &content);
+    }
+    hash
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for path in sorted_entries(dir) {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Continues the 64 bit FNV-1a hash `hash` with `bytes`.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
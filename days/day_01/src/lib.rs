@@ -1,25 +1,326 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    get_calorioes(input)
-        .max()
-        .expect("No calories found")
-        .to_string()
-}
-
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let mut calories = get_calorioes(input).collect::<Vec<_>>();
-    calories.sort_unstable();
-    calories.reverse();
-
-    calories[..3].iter().sum::<u64>().to_string()
-}
-
-fn get_calorioes(input: &str) -> impl Iterator<Item = u64> + '_ {
-    input.split("\n\n").map(|calories_raw| {
-        calories_raw
-            .split('\n')
-            .filter_map(|calorie| calorie.parse::<u64>().ok())
-            .sum::<u64>()
-    })
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead, str::FromStr};
+
+use common::{for_each_line, Answer, Solution, SolveError, SolveResult};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    type Input = Inventory;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        input.parse()
+    }
+
+    fn part_a(inventory: &Self::Input) -> SolveResult<Answer> {
+        max_calories(inventory.top(1).first().map(|winner| winner.calories))
+    }
+
+    fn part_b(inventory: &Self::Input) -> SolveResult<Answer> {
+        top_three_calories(inventory.top(3))
+    }
+
+    fn stream_a(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut max = None;
+        Some(
+            for_each_elf(reader, |_, calories| max = max.max(Some(calories)))
+                .and_then(|()| max_calories(max)),
+        )
+    }
+
+    fn stream_b(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut top = TopN::new(3);
+        let mut elf = 0;
+        Some(
+            for_each_elf(reader, |_, calories| {
+                top.push(elf, calories);
+                elf += 1;
+            })
+            .and_then(|()| top_three_calories(top.into_winners())),
+        )
+    }
+}
+
+fn max_calories(max: Option<u64>) -> SolveResult<Answer> {
+    max.map(Answer::from)
+        .ok_or_else(|| "No calories found".into())
+}
+
+fn top_three_calories(winners: Vec<Winner>) -> SolveResult<Answer> {
+    if winners.len() < 3 {
+        return Err(format!("Expected at least 3 elves, found {}", winners.len()).into());
+    }
+    Ok(winners.iter().map(|w| w.calories).sum::<u64>().into())
+}
+
+/// The items carried by every elf, in the order of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inventory {
+    items: Vec<Vec<u64>>,
+    totals: Vec<u64>,
+}
+
+/// An elf among those carrying the most calories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Winner {
+    /// 0-based position of the elf in the input.
+    pub elf: usize,
+    pub calories: u64,
+}
+
+impl Inventory {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.totals.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    /// Calories of each item carried by `elf`.
+    #[must_use]
+    pub fn items(&self, elf: usize) -> Option<&[u64]> {
+        self.items.get(elf).map(Vec::as_slice)
+    }
+
+    /// Total calories carried by each elf.
+    #[must_use]
+    pub fn totals(&self) -> &[u64] {
+        &self.totals
+    }
+
+    /// The `n` elves carrying the most calories, most first, or every elf
+    /// when there are fewer. Ties go to the elf first in the input.
+    #[must_use]
+    pub fn top(&self, n: usize) -> Vec<Winner> {
+        let mut top = TopN::new(n);
+        for (elf, &calories) in self.totals.iter().enumerate() {
+            top.push(elf, calories);
+        }
+        top.into_winners()
+    }
+
+    /// Calories carried by the `n` elves carrying the most, or by every elf
+    /// when there are fewer.
+    #[must_use]
+    pub fn top_sum(&self, n: usize) -> u64 {
+        self.top(n).iter().map(|w| w.calories).sum()
+    }
+
+    /// Average calories carried by an elf.
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let sum = self.totals.iter().map(|&c| u128::from(c)).sum::<u128>();
+        Some(sum as f64 / self.len() as f64)
+    }
+
+    /// Middle total, or the mean of both middle totals for an even number of
+    /// elves.
+    #[must_use]
+    pub fn median(&self) -> Option<f64> {
+        let sorted = self.sorted_totals();
+        let mid = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(sorted[mid] as f64),
+            _ => Some((sorted[mid - 1] as f64 + sorted[mid] as f64) / 2.0),
+        }
+    }
+
+    /// Smallest total reached by at least `p` percent of the elves when
+    /// ranked from fewest calories, using the nearest rank. `None` without
+    /// elves or if `p` is not within `0..=100`.
+    #[must_use]
+    pub fn percentile(&self, p: f64) -> Option<u64> {
+        if self.is_empty() || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let sorted = self.sorted_totals();
+        let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+
+    fn sorted_totals(&self) -> Vec<u64> {
+        let mut sorted = self.totals.clone();
+        sorted.sort_unstable();
+        sorted
+    }
+}
+
+impl FromStr for Inventory {
+    type Err = SolveError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut inventory = Self {
+            items: Vec::new(),
+            totals: Vec::new(),
+        };
+        for_each_elf(&mut input.as_bytes(), |items, total| {
+            inventory.items.push(items.to_vec());
+            inventory.totals.push(total);
+        })?;
+        Ok(inventory)
+    }
+}
+
+/// The `n` largest values pushed so far, in a min-heap of at most `n`
+/// values so memory does not grow with the number of elves.
+struct TopN {
+    n: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopN {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, elf: usize, calories: u64) {
+        self.heap.push(Reverse((calories, Reverse(elf))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// Largest first, the earliest elf first among equals.
+    fn into_winners(self) -> Vec<Winner> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(elf)))| Winner { elf, calories })
+            .collect()
+    }
+}
+
+/// Calls `f` with the items and total calories of every elf of `reader`.
+fn for_each_elf(reader: &mut dyn BufRead, mut f: impl FnMut(&[u64], u64)) -> SolveResult<()> {
+    let mut items = Vec::new();
+    let mut current = None;
+    for_each_line(reader, |i, line| {
+        if line.is_empty() {
+            if let Some(calories) = current.take() {
+                f(&items, calories);
+                items.clear();
+            }
+            return Ok(());
+        }
+        let calorie = line
+            .parse::<u64>()
+            .map_err(|e| SolveError::at_line(i, format!("Invalid calories {line:?}: {e}")))?;
+        let total = current.unwrap_or(0).checked_add(calorie);
+        current = Some(total.ok_or_else(|| SolveError::at_line(i, "Too many calories"))?);
+        items.push(calorie);
+        Ok(())
+    })?;
+    if let Some(calories) = current {
+        f(&items, calories);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_inventory() {
+        let inventory = include_str!("../../../inputs/day_01/test_input.txt")
+            .parse::<Inventory>()
+            .unwrap();
+        assert_eq!(inventory.len(), 5);
+        assert_eq!(inventory.items(0), Some(&[1000, 2000, 3000][..]));
+        assert_eq!(inventory.items(5), None);
+        assert_eq!(inventory.totals(), [6000, 4000, 11000, 24000, 10000]);
+        let elves = inventory.top(3).iter().map(|w| w.elf).collect::<Vec<_>>();
+        assert_eq!(elves, [3, 2, 4]);
+        assert_eq!(inventory.top_sum(3), 45000);
+        assert_eq!(inventory.mean(), Some(11000.0));
+        assert_eq!(inventory.median(), Some(10000.0));
+        assert_eq!(inventory.percentile(0.0), Some(4000));
+        assert_eq!(inventory.percentile(40.0), Some(6000));
+        assert_eq!(inventory.percentile(100.0), Some(24000));
+        assert_eq!(inventory.percentile(101.0), None);
+    }
+
+    #[test]
+    fn test_fewer_elves_than_top() {
+        let inventory = "5\n\n7\n7\n\n14\n".parse::<Inventory>().unwrap();
+        let top = inventory.top(3);
+        assert_eq!(
+            top,
+            [
+                Winner {
+                    elf: 1,
+                    calories: 14
+                },
+                Winner {
+                    elf: 2,
+                    calories: 14
+                },
+                Winner {
+                    elf: 0,
+                    calories: 5
+                },
+            ]
+        );
+        assert_eq!(inventory.top(5), top);
+        assert!(inventory.top(0).is_empty());
+        assert_eq!(inventory.median(), Some(14.0));
+
+        let inventory = "1\n\n2\n".parse::<Inventory>().unwrap();
+        assert_eq!(inventory.top_sum(3), 3);
+        assert_eq!(inventory.median(), Some(1.5));
+        assert!(Day01::part_b(&inventory).is_err());
+        let empty = "".parse::<Inventory>().unwrap();
+        assert_eq!(
+            (empty.mean(), empty.median(), empty.percentile(50.0)),
+            (None, None, None)
+        );
+    }
+
+    #[test]
+    fn test_calories_overflow() {
+        let error = Day01::parse("1\n\n18446744073709551615\n1\n").unwrap_err();
+        assert_eq!(error.line, Some(4));
+    }
+
+    /// Totals of every elf, largest first.
+    fn naive_totals(input: &str) -> Vec<u64> {
+        let mut totals = input
+            .split("\n\n")
+            .map(|elf| elf.lines().map(|l| l.parse::<u64>().unwrap()).sum::<u64>())
+            .collect::<Vec<_>>();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        totals
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..50_usize) {
+            let input = generate::to_string(Day01::DAY, size, seed).unwrap();
+            let totals = naive_totals(&input);
+            prop_assert_eq!(Day01::solve_a(&input), Ok(totals[0].into()));
+            let top_three = totals[..3].iter().sum::<u64>();
+            prop_assert_eq!(Day01::solve_b(&input), Ok(top_three.into()));
+            let streamed = Day01::stream_b(&mut input.as_bytes());
+            prop_assert_eq!(streamed, Some(Ok(top_three.into())));
+            let inventory = Day01::parse(&input).unwrap();
+            for n in 0..=totals.len() + 1 {
+                let expected = totals[..n.min(totals.len())].iter().sum::<u64>();
+                prop_assert_eq!(inventory.top_sum(n), expected);
+            }
+        }
+    }
 }
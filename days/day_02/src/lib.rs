@@ -1,97 +1,199 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let (s1, s2) = line.split_once(' ').and_then(|(s1, s2)| {
-                Some((Shape::try_from(s1).ok()?, Shape::try_from(s2).ok()?))
-            })?;
-            let round_result = s2.wins(s1);
-            Some(u64::from(s2.shape_score() + round_result.round_score()))
-        })
-        .sum::<u64>()
-        .to_string()
+mod optimize;
+mod rules;
+
+use std::io::BufRead;
+
+use common::{for_each_line, Answer, Solution, SolveError, SolveResult};
+
+pub use optimize::{optimize, Goal, RoundScore, Strategy};
+pub use rules::{Interpretation, Outcome, Rules, STANDARD};
+
+use rules::parse_letter;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    /// Rounds checked against the standard rules.
+    type Input = Vec<Round>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        let rules = Rules::standard();
+        let mut rounds = Vec::new();
+        for_each_round(&mut input.as_bytes(), |round| {
+            rules.check_round(round.first, round.second)?;
+            rounds.push(round);
+            Ok(())
+        })?;
+        Ok(rounds)
+    }
+
+    fn part_a(rounds: &Self::Input) -> SolveResult<Answer> {
+        total_score(Rules::standard(), rounds, Interpretation::Shape).map(Answer::from)
+    }
+
+    fn part_b(rounds: &Self::Input) -> SolveResult<Answer> {
+        total_score(Rules::standard(), rounds, Interpretation::Outcome).map(Answer::from)
+    }
+
+    fn stream_a(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        Some(stream_score(reader, Interpretation::Shape).map(Answer::from))
+    }
+
+    fn stream_b(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        Some(stream_score(reader, Interpretation::Outcome).map(Answer::from))
+    }
+}
+
+/// Both letters of a line of the guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    /// 0-based line of the input, to locate errors.
+    pub line: usize,
+    pub first: char,
+    pub second: char,
 }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let (s1, mut s2) = line.split_once(' ').and_then(|(s1, s2)| {
-                Some((Shape::try_from(s1).ok()?, Shape::try_from(s2).ok()?))
-            })?;
-            let round_result = match &s2 {
-                Shape::Rock => RoundResult::Loss,
-                Shape::Paper => RoundResult::Draw,
-                Shape::Scissors => RoundResult::Win,
-            };
-            s2 = match (&s1, &round_result) {
-                (s, RoundResult::Draw) => *s,
-                (s, RoundResult::Win) => match s {
-                    Shape::Rock => Shape::Paper,
-                    Shape::Paper => Shape::Scissors,
-                    Shape::Scissors => Shape::Rock,
-                },
-                (s, RoundResult::Loss) => match s {
-                    Shape::Rock => Shape::Scissors,
-                    Shape::Paper => Shape::Rock,
-                    Shape::Scissors => Shape::Paper,
-                },
-            };
-
-            Some(u64::from(s2.shape_score() + round_result.round_score()))
+/// Sum of the scores of `rounds` under `rules`, reading the second column as
+/// `interpretation` says.
+///
+/// # Errors
+/// If a round has no score under `rules`, see [`Rules::score_round`].
+pub fn total_score(
+    rules: &Rules,
+    rounds: &[Round],
+    interpretation: Interpretation,
+) -> SolveResult<u64> {
+    rounds
+        .iter()
+        .map(|round| {
+            rules
+                .score_round(round.first, round.second, interpretation)
+                .map_err(|e| SolveError::at_line(round.line, e))
         })
-        .sum::<u64>()
-        .to_string()
+        .sum()
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Shape {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+fn stream_score(reader: &mut dyn BufRead, interpretation: Interpretation) -> SolveResult<u64> {
+    let rules = Rules::standard();
+    let mut score = 0;
+    for_each_round(reader, |round| {
+        score += rules.score_round(round.first, round.second, interpretation)?;
+        Ok(())
+    })?;
+    Ok(score)
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum RoundResult {
-    Win = 6,
-    Loss = 0,
-    Draw = 3,
+/// Calls `f` with every round of `reader`, skipping empty lines.
+fn for_each_round(
+    reader: &mut dyn BufRead,
+    mut f: impl FnMut(Round) -> Result<(), String>,
+) -> SolveResult<()> {
+    for_each_line(reader, |i, line| {
+        if line.is_empty() {
+            return Ok(());
+        }
+        let invalid = || SolveError::at_line(i, format!("Invalid round: {line}"));
+        let (first, second) = line.split_once(' ').ok_or_else(invalid)?;
+        let round = Round {
+            line: i,
+            first: parse_letter(first).map_err(|_| invalid())?,
+            second: parse_letter(second).map_err(|_| invalid())?,
+        };
+        f(round).map_err(|e| SolveError::at_line(i, e))
+    })
 }
 
-impl Shape {
-    const fn shape_score(self) -> u8 {
-        self as u8
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Score of every possible round, reading the second column as a shape
+    /// and as a result.
+    const SCORES: [(&str, u64, u64); 9] = [
+        ("A X", 4, 3),
+        ("A Y", 8, 4),
+        ("A Z", 3, 8),
+        ("B X", 1, 1),
+        ("B Y", 5, 5),
+        ("B Z", 9, 9),
+        ("C X", 7, 2),
+        ("C Y", 2, 6),
+        ("C Z", 6, 7),
+    ];
+
+    fn naive_scores(input: &str) -> (u64, u64) {
+        input.lines().fold((0, 0), |(a, b), line| {
+            let &(_, round_a, round_b) = SCORES.iter().find(|(r, ..)| *r == line).unwrap();
+            (a + round_a, b + round_b)
+        })
     }
 
-    fn wins(self, other: Self) -> RoundResult {
-        match (self, other) {
-            (a, b) if a == b => RoundResult::Draw,
-            (Self::Rock, Self::Scissors)
-            | (Self::Paper, Self::Rock)
-            | (Self::Scissors, Self::Paper) => RoundResult::Win,
-            _ => RoundResult::Loss,
-        }
+    #[test]
+    fn test_interpretations() {
+        let rounds = Day02::parse("A Y\nB X\nC Z\n").unwrap();
+        let rules = Rules::standard();
+        assert_eq!(total_score(rules, &rounds, Interpretation::Shape), Ok(15));
+        assert_eq!(total_score(rules, &rounds, Interpretation::Outcome), Ok(12));
+
+        // Same game, but a draw is worth nothing and shapes score double.
+        let custom = STANDARD
+            .replace("score draw 3", "score draw 0")
+            .replace("paper 2", "paper 4")
+            .replace("scissors 3", "scissors 6")
+            .replace("rock 1", "rock 2");
+        let rules = custom.parse::<Rules>().unwrap();
+        assert_eq!(total_score(&rules, &rounds, Interpretation::Shape), Ok(18));
+        assert_eq!(
+            total_score(&rules, &rounds, Interpretation::Outcome),
+            Ok(12)
+        );
     }
-}
 
-impl TryFrom<&str> for Shape {
-    type Error = String;
-    fn try_from(shape: &str) -> Result<Self, Self::Error> {
-        match shape {
-            "A" | "X" => Ok(Self::Rock),
-            "B" | "Y" => Ok(Self::Paper),
-            "C" | "Z" => Ok(Self::Scissors),
-            _ => Err(format!("Invalid shape: {shape}")),
-        }
+    #[test]
+    fn test_invalid_rounds() {
+        assert_eq!(Day02::parse("A X\nD X\n").unwrap_err().line, Some(2));
+        assert_eq!(Day02::parse("A X\n\nA W\n").unwrap_err().line, Some(3));
+        assert_eq!(Day02::solve_b("A Y\nAX\n").unwrap_err().line, Some(2));
+        assert_eq!(Day02::parse("A XY\n").unwrap_err().line, Some(1));
+
+        // Nothing beats scissors any more.
+        let rules = STANDARD
+            .replace("rock beats scissors", "")
+            .parse::<Rules>()
+            .unwrap();
+        let rounds = Day02::parse("A Y\n\nC Z\n").unwrap();
+        let error = total_score(&rules, &rounds, Interpretation::Outcome).unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.reason, "No shape reaches win against scissors");
     }
-}
 
-impl RoundResult {
-    pub const fn round_score(self) -> u8 {
-        self as u8
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..100_usize) {
+            let input = generate::to_string(Day02::DAY, size, seed).unwrap();
+            let (a, b) = naive_scores(&input);
+            prop_assert_eq!(Day02::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day02::solve_b(&input), Ok(b.into()));
+        }
+
+        #[test]
+        fn test_optimum_bounds_guide(seed: u64, size in 0..100_usize) {
+            let input = generate::to_string(Day02::DAY, size, seed).unwrap();
+            let rounds = Day02::parse(&input).unwrap();
+            let rules = Rules::standard();
+            for interpretation in [Interpretation::Shape, Interpretation::Outcome] {
+                let guide = total_score(rules, &rounds, interpretation).unwrap();
+                let max = optimize(rules, &rounds, interpretation, Goal::Max).unwrap();
+                let min = optimize(rules, &rounds, interpretation, Goal::Min).unwrap();
+                prop_assert!(min.score <= guide && guide <= max.score);
+                let breakdown = max.rounds.iter().map(|r| r.score).sum::<u64>();
+                prop_assert_eq!(breakdown, max.score);
+            }
+        }
     }
 }
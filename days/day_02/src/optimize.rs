@@ -0,0 +1,294 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use common::{SolveError, SolveResult};
+
+use crate::{Interpretation, Outcome, Round, Rules};
+
+/// Whether [`optimize`] looks for the highest or the lowest total score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Max,
+    Min,
+}
+
+/// One round played with a mapping found by [`optimize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundScore {
+    pub opponent: String,
+    pub player: String,
+    pub outcome: Outcome,
+    pub score: u64,
+}
+
+/// Best mapping of the second column letters found by [`optimize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strategy {
+    /// Every second column letter, sorted, with the shape or outcome it
+    /// stands for.
+    pub mapping: Vec<(char, String)>,
+    pub score: u64,
+    /// Every round played with `mapping`, in order.
+    pub rounds: Vec<RoundScore>,
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mapping = self
+            .mapping
+            .iter()
+            .map(|(letter, meaning)| format!("{letter} = {meaning}"))
+            .collect::<Vec<_>>();
+        writeln!(f, "{}: {}", mapping.join(", "), self.score)?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                f,
+                "{}: {} against {}, {}, {}",
+                i + 1,
+                round.player,
+                round.opponent,
+                round.outcome,
+                round.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What a second column letter stands for.
+#[derive(Clone, Copy)]
+enum Choice {
+    Shape(usize),
+    Outcome(Outcome),
+}
+
+impl Choice {
+    /// Shape played against shape `opponent`, if any reaches the outcome.
+    fn play(self, rules: &Rules, opponent: usize) -> Option<usize> {
+        match self {
+            Self::Shape(player) => Some(player),
+            Self::Outcome(outcome) => rules.reaching(outcome, opponent),
+        }
+    }
+
+    fn name(self, rules: &Rules) -> String {
+        match self {
+            Self::Shape(shape) => rules.shapes()[shape].clone(),
+            Self::Outcome(outcome) => outcome.to_string(),
+        }
+    }
+}
+
+/// Tries every mapping of the second column letters of `rounds` to distinct
+/// shapes or outcomes, as `interpretation` says, keeping the one with the
+/// best total score for `goal`. Ties keep the mapping found first, trying
+/// shapes in declaration order and outcomes from loss to win.
+///
+/// Rounds are counted by letter pair first, so each mapping is scored
+/// without replaying the whole guide.
+///
+/// # Errors
+/// If an opponent letter is unknown, there are more letters than shapes or
+/// outcomes, or no mapping reaches every wanted outcome.
+pub fn optimize(
+    rules: &Rules,
+    rounds: &[Round],
+    interpretation: Interpretation,
+    goal: Goal,
+) -> SolveResult<Strategy> {
+    let letters = rounds
+        .iter()
+        .map(|round| round.second)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let choices = match interpretation {
+        Interpretation::Shape => (0..rules.shapes().len()).map(Choice::Shape).collect(),
+        Interpretation::Outcome => Outcome::ALL.map(Choice::Outcome).to_vec(),
+    };
+    if letters.len() > choices.len() {
+        return Err(format!(
+            "Cannot map {} letters to {} choices",
+            letters.len(),
+            choices.len()
+        )
+        .into());
+    }
+
+    let mut plays = Vec::with_capacity(rounds.len());
+    let mut counts = HashMap::<_, u64>::new();
+    for round in rounds {
+        let opponent = rules
+            .opponent_shape(round.first)
+            .map_err(|e| SolveError::at_line(round.line, e))?;
+        let letter = letters.binary_search(&round.second).unwrap_or_default();
+        plays.push((letter, opponent));
+        *counts.entry((letter, opponent)).or_default() += 1;
+    }
+
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    for_each_assignment(letters.len(), choices.len(), &mut |assignment| {
+        let score = counts
+            .iter()
+            .try_fold(0, |score, (&(letter, opponent), &count)| {
+                let player = choices[assignment[letter]].play(rules, opponent)?;
+                Some(score + count * rules.score(player, opponent))
+            });
+        let Some(score) = score else {
+            return;
+        };
+        let better = best.as_ref().is_none_or(|&(best, _)| match goal {
+            Goal::Max => score > best,
+            Goal::Min => score < best,
+        });
+        if better {
+            best = Some((score, assignment.to_vec()));
+        }
+    });
+    let (score, assignment) =
+        best.ok_or_else(|| SolveError::new("No mapping reaches every wanted outcome"))?;
+
+    let mapping = letters
+        .iter()
+        .zip(&assignment)
+        .map(|(&letter, &choice)| (letter, choices[choice].name(rules)))
+        .collect();
+    let rounds = plays
+        .into_iter()
+        .map(|(letter, opponent)| {
+            let player = choices[assignment[letter]]
+                .play(rules, opponent)
+                .expect("the best mapping reaches every outcome");
+            RoundScore {
+                opponent: rules.shapes()[opponent].clone(),
+                player: rules.shapes()[player].clone(),
+                outcome: rules.outcome(player, opponent),
+                score: rules.score(player, opponent),
+            }
+        })
+        .collect();
+    Ok(Strategy {
+        mapping,
+        score,
+        rounds,
+    })
+}
+
+/// Calls `f` with every assignment of `k` items to distinct slots among `n`,
+/// in lexicographic order.
+fn for_each_assignment(k: usize, n: usize, f: &mut impl FnMut(&[usize])) {
+    fn extend(
+        assignment: &mut Vec<usize>,
+        used: &mut [bool],
+        k: usize,
+        f: &mut impl FnMut(&[usize]),
+    ) {
+        if assignment.len() == k {
+            f(assignment);
+            return;
+        }
+        for slot in 0..used.len() {
+            if !used[slot] {
+                used[slot] = true;
+                assignment.push(slot);
+                extend(assignment, used, k, f);
+                assignment.pop();
+                used[slot] = false;
+            }
+        }
+    }
+    extend(&mut Vec::with_capacity(k), &mut vec![false; n], k, f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rounds of `input`, unchecked so they may break the rules.
+    fn rounds(input: &str) -> Vec<Round> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(line, round)| {
+                let mut letters = round.chars();
+                let first = letters.next().unwrap();
+                let second = letters.nth(1).unwrap();
+                Round {
+                    line,
+                    first,
+                    second,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_for_each_assignment() {
+        let mut assignments = Vec::new();
+        for_each_assignment(2, 3, &mut |a| assignments.push(a.to_vec()));
+        assert_eq!(
+            assignments,
+            [[0, 1], [0, 2], [1, 0], [1, 2], [2, 0], [2, 1]]
+        );
+        let mut count = 0;
+        for_each_assignment(0, 3, &mut |_| count += 1);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_optimize() {
+        let rules = Rules::standard();
+        let rounds = rounds("A Y\nB X\nC Z");
+        let optimize = |interpretation, goal| optimize(rules, &rounds, interpretation, goal);
+
+        let best = optimize(Interpretation::Shape, Goal::Max).unwrap();
+        assert_eq!(best.score, 24);
+        assert_eq!(
+            best.mapping,
+            [('X', "scissors"), ('Y', "paper"), ('Z', "rock")].map(|(l, m)| (l, m.to_string()))
+        );
+        assert_eq!(
+            best.rounds.iter().map(|r| r.score).collect::<Vec<_>>(),
+            [8, 9, 7]
+        );
+        assert_eq!(best.rounds[1].outcome, Outcome::Win);
+        assert_eq!(
+            best.to_string(),
+            "X = scissors, Y = paper, Z = rock: 24\n\
+             1: paper against rock, win, 8\n\
+             2: scissors against paper, win, 9\n\
+             3: rock against scissors, win, 7\n"
+        );
+
+        assert_eq!(optimize(Interpretation::Shape, Goal::Min).unwrap().score, 6);
+        let best = optimize(Interpretation::Outcome, Goal::Max).unwrap();
+        assert_eq!(best.score, 18);
+        assert_eq!(best.mapping[0], ('X', "win".to_string()));
+        // The guide as written is already the worst reading.
+        assert_eq!(
+            optimize(Interpretation::Outcome, Goal::Min).unwrap().score,
+            12
+        );
+    }
+
+    #[test]
+    fn test_optimize_errors() {
+        let rules = Rules::standard();
+        let error = optimize(
+            rules,
+            &rounds("A W\nB X\nC Y\nA Z"),
+            Interpretation::Shape,
+            Goal::Max,
+        );
+        assert_eq!(
+            error.unwrap_err().reason,
+            "Cannot map 4 letters to 3 choices"
+        );
+        let error =
+            optimize(rules, &rounds("A X\nD Y"), Interpretation::Shape, Goal::Max).unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.reason, "Invalid opponent letter: D");
+    }
+}
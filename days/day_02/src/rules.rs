@@ -0,0 +1,372 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr, sync::LazyLock};
+
+use common::SolveError;
+
+/// The rules of the puzzle, in the format read by [`Rules::from_str`].
+pub const STANDARD: &str = "\
+shape rock 1
+shape paper 2
+shape scissors 3
+
+rock beats scissors
+paper beats rock
+scissors beats paper
+
+score loss 0
+score draw 3
+score win 6
+
+opponent A rock
+opponent B paper
+opponent C scissors
+player X rock
+player Y paper
+player Z scissors
+outcome X loss
+outcome Y draw
+outcome Z win
+";
+
+static STANDARD_RULES: LazyLock<Rules> =
+    LazyLock::new(|| STANDARD.parse().expect("standard rules are valid"));
+
+/// How the second column of a round is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The shape to play, part a of the puzzle.
+    Shape,
+    /// The outcome to reach, playing the first shape that reaches it, part b
+    /// of the puzzle.
+    Outcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Self; 3] = [Self::Loss, Self::Draw, Self::Win];
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Loss => "loss",
+            Self::Draw => "draw",
+            Self::Win => "win",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A game of hand shapes, described entirely by data: its shapes and their
+/// scores, which shape beats which, the score of each outcome and what the
+/// letters of both columns mean.
+///
+/// Rules are read from lines like those of [`STANDARD`], in any order as long
+/// as shapes are declared before being used. Blank lines and lines starting
+/// with `#` are ignored:
+///
+/// - `shape <name> <score>` declares a shape scoring `score` when played.
+/// - `<shape> beats <shape>`; shapes unrelated either way draw.
+/// - `score <loss|draw|win> <score>`, required for every outcome.
+/// - `opponent <letter> <shape>`, the first column.
+/// - `player <letter> <shape>` and `outcome <letter> <loss|draw|win>`, the
+///   second column read as a shape or as an outcome.
+///
+/// Letters are single characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    shapes: Vec<String>,
+    shape_scores: Vec<u64>,
+    /// `beats[a][b]` if shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+    outcome_scores: HashMap<Outcome, u64>,
+    opponent: HashMap<char, usize>,
+    player: HashMap<char, usize>,
+    outcomes: HashMap<char, Outcome>,
+}
+
+impl Rules {
+    /// The rules of the puzzle, see [`STANDARD`], parsed once.
+    #[must_use]
+    pub fn standard() -> &'static Self {
+        &STANDARD_RULES
+    }
+
+    /// Names of the shapes, in declaration order.
+    #[must_use]
+    pub fn shapes(&self) -> &[String] {
+        &self.shapes
+    }
+
+    /// Outcome for the player of shape `player` against shape `opponent`.
+    #[must_use]
+    pub fn outcome(&self, player: usize, opponent: usize) -> Outcome {
+        if self.beats[player][opponent] {
+            Outcome::Win
+        } else if self.beats[opponent][player] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// Score of playing shape `player` against shape `opponent`.
+    #[must_use]
+    pub fn score(&self, player: usize, opponent: usize) -> u64 {
+        self.shape_scores[player] + self.outcome_scores[&self.outcome(player, opponent)]
+    }
+
+    /// Score of the round `first second`, reading `second` as
+    /// `interpretation` says.
+    ///
+    /// # Errors
+    /// If a letter means nothing in these rules, or no shape reaches the
+    /// wanted outcome.
+    pub fn score_round(
+        &self,
+        first: char,
+        second: char,
+        interpretation: Interpretation,
+    ) -> Result<u64, String> {
+        let opponent = self.opponent_shape(first)?;
+        let player = match interpretation {
+            Interpretation::Shape => *self
+                .player
+                .get(&second)
+                .ok_or_else(|| format!("Invalid player letter: {second}"))?,
+            Interpretation::Outcome => {
+                let outcome = *self
+                    .outcomes
+                    .get(&second)
+                    .ok_or_else(|| format!("Invalid outcome letter: {second}"))?;
+                self.reaching(outcome, opponent).ok_or_else(|| {
+                    format!(
+                        "No shape reaches {outcome} against {}",
+                        self.shapes[opponent]
+                    )
+                })?
+            }
+        };
+        Ok(self.score(player, opponent))
+    }
+
+    /// Shape played by the opponent for the first column letter `letter`.
+    ///
+    /// # Errors
+    /// If the letter means nothing in these rules.
+    pub fn opponent_shape(&self, letter: char) -> Result<usize, String> {
+        self.opponent
+            .get(&letter)
+            .copied()
+            .ok_or_else(|| format!("Invalid opponent letter: {letter}"))
+    }
+
+    /// First shape, in declaration order, reaching `outcome` against shape
+    /// `opponent`.
+    #[must_use]
+    pub fn reaching(&self, outcome: Outcome, opponent: usize) -> Option<usize> {
+        (0..self.shapes.len()).find(|&player| self.outcome(player, opponent) == outcome)
+    }
+
+    /// Checks that both letters mean something in at least one
+    /// interpretation.
+    ///
+    /// # Errors
+    /// If a letter is unknown.
+    pub fn check_round(&self, first: char, second: char) -> Result<(), String> {
+        if !self.opponent.contains_key(&first) {
+            return Err(format!("Invalid opponent letter: {first}"));
+        }
+        if !self.player.contains_key(&second) && !self.outcomes.contains_key(&second) {
+            return Err(format!("Invalid player letter: {second}"));
+        }
+        Ok(())
+    }
+
+    fn shape(&self, name: &str) -> Result<usize, String> {
+        self.shapes
+            .iter()
+            .position(|shape| shape == name)
+            .ok_or_else(|| format!("Unknown shape: {name}"))
+    }
+}
+
+impl FromStr for Rules {
+    type Err = SolveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self {
+            shapes: Vec::new(),
+            shape_scores: Vec::new(),
+            beats: Vec::new(),
+            outcome_scores: HashMap::new(),
+            opponent: HashMap::new(),
+            player: HashMap::new(),
+            outcomes: HashMap::new(),
+        };
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            add_rule(&mut rules, line).map_err(|e| SolveError::at_line(i, e))?;
+        }
+        for outcome in Outcome::ALL {
+            if !rules.outcome_scores.contains_key(&outcome) {
+                return Err(format!("Missing score of {outcome}").into());
+            }
+        }
+        Ok(rules)
+    }
+}
+
+fn add_rule(rules: &mut Rules, line: &str) -> Result<(), String> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["shape", name, score] => {
+            if rules.shape(name).is_ok() {
+                return Err(format!("Duplicate shape: {name}"));
+            }
+            let score = score
+                .parse()
+                .map_err(|e| format!("Invalid score {score:?}: {e}"))?;
+            rules.shapes.push(name.to_string());
+            rules.shape_scores.push(score);
+            rules.beats.iter_mut().for_each(|row| row.push(false));
+            rules.beats.push(vec![false; rules.shapes.len()]);
+        }
+        [winner, "beats", loser] => {
+            let (winner, loser) = (rules.shape(winner)?, rules.shape(loser)?);
+            if winner == loser || rules.beats[loser][winner] {
+                return Err(format!("Contradictory rule: {line}"));
+            }
+            rules.beats[winner][loser] = true;
+        }
+        ["score", outcome, score] => {
+            let score = score
+                .parse()
+                .map_err(|e| format!("Invalid score {score:?}: {e}"))?;
+            if rules
+                .outcome_scores
+                .insert(parse_outcome(outcome)?, score)
+                .is_some()
+            {
+                return Err(format!("Duplicate score of {outcome}"));
+            }
+        }
+        ["opponent", letter, shape] => {
+            let shape = rules.shape(shape)?;
+            insert_letter(&mut rules.opponent, letter, shape)?;
+        }
+        ["player", letter, shape] => {
+            let shape = rules.shape(shape)?;
+            insert_letter(&mut rules.player, letter, shape)?;
+        }
+        ["outcome", letter, outcome] => {
+            insert_letter(&mut rules.outcomes, letter, parse_outcome(outcome)?)?;
+        }
+        _ => return Err(format!("Invalid rule: {line}")),
+    }
+    Ok(())
+}
+
+fn insert_letter<T>(letters: &mut HashMap<char, T>, letter: &str, value: T) -> Result<(), String> {
+    if letters.insert(parse_letter(letter)?, value).is_some() {
+        return Err(format!("Duplicate letter: {letter}"));
+    }
+    Ok(())
+}
+
+/// `s` if it is a single character, as letters of the guide are.
+pub(crate) fn parse_letter(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) => Ok(letter),
+        _ => Err(format!("Invalid letter: {s}")),
+    }
+}
+
+fn parse_outcome(s: &str) -> Result<Outcome, String> {
+    Outcome::ALL
+        .into_iter()
+        .find(|outcome| outcome.to_string() == s)
+        .ok_or_else(|| format!("Invalid outcome: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rock paper scissors lizard Spock, where winning is worth a lot more.
+    const RPSLS: &str = "\
+shape rock 1
+shape paper 2
+shape scissors 3
+shape lizard 4
+shape spock 5
+scissors beats paper
+paper beats rock
+rock beats lizard
+lizard beats spock
+spock beats scissors
+scissors beats lizard
+lizard beats paper
+paper beats spock
+spock beats rock
+rock beats scissors
+score loss 0
+score draw 5
+score win 10
+opponent A rock
+opponent B paper
+opponent C scissors
+opponent D lizard
+opponent E spock
+player V rock
+player W paper
+player X scissors
+player Y lizard
+player Z spock
+outcome X loss
+outcome Y draw
+outcome Z win
+";
+
+    #[test]
+    fn test_rpsls() {
+        let rules = RPSLS.parse::<Rules>().unwrap();
+        assert_eq!(rules.shapes().len(), 5);
+        assert_eq!(rules.score_round('E', 'Y', Interpretation::Shape), Ok(14));
+        assert_eq!(rules.score_round('A', 'Z', Interpretation::Shape), Ok(15));
+        assert_eq!(rules.score_round('C', 'X', Interpretation::Shape), Ok(8));
+        // Paper and Spock both beat rock, paper comes first.
+        assert_eq!(rules.score_round('A', 'Z', Interpretation::Outcome), Ok(12));
+        assert_eq!(rules.score_round('D', 'X', Interpretation::Outcome), Ok(2));
+        assert_eq!(
+            rules.score_round('A', 'V', Interpretation::Outcome),
+            Err("Invalid outcome letter: V".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let line_of = |rules: &str| rules.parse::<Rules>().unwrap_err().line;
+        assert_eq!(line_of("shape rock 1\nshape rock 2\n"), Some(2));
+        assert_eq!(line_of("shape rock 1\nrock beats paper\n"), Some(2));
+        assert_eq!(
+            line_of("shape a 1\nshape b 2\na beats b\nb beats a\n"),
+            Some(4)
+        );
+        assert_eq!(line_of("shape a 1\nopponent A a\nopponent A a\n"), Some(3));
+        assert_eq!(line_of("score tie 3\n"), Some(1));
+        assert_eq!(line_of("shape a 1\nopponent AB a\n"), Some(2));
+        let error = "shape a 1\nscore loss 0\nscore win 6\n"
+            .parse::<Rules>()
+            .unwrap_err();
+        assert_eq!(error.reason, "Missing score of draw");
+    }
+}
@@ -1,46 +1,328 @@
-use std::collections::HashSet;
+use std::{
+    fmt::Display,
+    io::BufRead,
+    ops::{BitAnd, BitOr},
+};
+
+use common::{for_each_line, normalize, Answer, Solution, SolveError, SolveResult};
 
 const LOWER_A_DEC: u64 = 97;
 const UPPER_A_DEC: u64 = 65;
+const GROUP_SIZE: usize = 3;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    type Input = Vec<Contents>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        let mut rucksacks = Vec::new();
+        for_each_rucksack(&mut input.as_bytes(), |contents| {
+            rucksacks.push(contents);
+            Ok(())
+        })?;
+        Ok(rucksacks)
+    }
+
+    fn part_a(rucksacks: &Self::Input) -> SolveResult<Answer> {
+        rucksacks
+            .iter()
+            .map(|&contents| shared_priority(contents))
+            .sum::<SolveResult<u64>>()
+            .map(Answer::from)
+    }
+
+    fn part_b(rucksacks: &Self::Input) -> SolveResult<Answer> {
+        badge_sum(rucksacks, GROUP_SIZE).map(Answer::from)
+    }
+
+    fn stream_a(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut sum = 0;
+        Some(
+            for_each_rucksack(reader, |contents| {
+                sum += shared_priority(contents)?;
+                Ok(())
+            })
+            .map(|()| sum.into()),
+        )
+    }
+
+    fn stream_b(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut sum = 0;
+        // First line, length and shared items of the current group.
+        let mut group = (0, 0, Rucksack::ALL);
+        let result = for_each_rucksack(reader, |contents| {
+            if group.1 == 0 {
+                group.0 = contents.line;
+            }
+            group.1 += 1;
+            group.2 = group.2 & contents.items();
+            if group.1 == GROUP_SIZE {
+                sum += badge_priority(group.0, group.2)?;
+                group = (0, 0, Rucksack::ALL);
+            }
+            Ok(())
+        });
+        Some(result.and_then(|()| {
+            if group.1 > 0 {
+                sum += badge_priority(group.0, group.2)?;
+            }
+            Ok(sum.into())
+        }))
+    }
+}
+
+/// Both compartments of a rucksack of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contents {
+    /// 0-based line of the input, to locate errors.
+    pub line: usize,
+    pub compartments: [Rucksack; 2],
+}
+
+impl Contents {
+    /// Items of both compartments.
+    #[must_use]
+    pub fn items(self) -> Rucksack {
+        let [left, right] = self.compartments;
+        left | right
+    }
+}
+
+/// Sum of the priorities of the badges of each group of `group_size`
+/// consecutive rucksacks, the last group being shorter if needed. A group
+/// without a badge adds nothing.
+///
+/// # Errors
+/// If a group shares several items, so its badge is ambiguous.
+///
+/// # Panics
+/// If `group_size` is 0.
+pub fn badge_sum(rucksacks: &[Contents], group_size: usize) -> SolveResult<u64> {
+    rucksacks
+        .chunks(group_size)
+        .map(|group| {
+            let badge = group
+                .iter()
+                .fold(Rucksack::ALL, |badge, contents| badge & contents.items());
+            badge_priority(group[0].line, badge)
+        })
+        .sum()
+}
+
+/// Priority of the item in both compartments, 0 if there is none.
+fn shared_priority(contents: Contents) -> SolveResult<u64> {
+    let [left, right] = contents.compartments;
+    let shared = left & right;
+    if shared.len() > 1 {
+        return Err(SolveError::at_line(
+            contents.line,
+            format!("Several items in both compartments: {shared}"),
+        ));
+    }
+    Ok(shared.priority_sum())
+}
+
+/// Priority of the `badge` of the group starting at the 0-based `line`, 0 if
+/// the group shares no item.
+fn badge_priority(line: usize, badge: Rucksack) -> SolveResult<u64> {
+    if badge.len() > 1 {
+        return Err(SolveError::at_line(
+            line,
+            format!("Ambiguous badge, the group shares {badge}"),
+        ));
+    }
+    Ok(badge.priority_sum())
+}
 
+/// Every way `input` breaks the puzzle rules, sorted by the line each one
+/// points at: invalid items, odd lengths, compartments sharing no item or
+/// several, and groups of `group_size` rucksacks, counted from the first line,
+/// that are incomplete or do not share exactly one badge. The input is
+/// normalized and empty lines are skipped as when solving.
+///
+/// # Panics
+/// If `group_size` is 0.
 #[must_use]
-pub fn calc_a(input: &str) -> String {
-    input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let (c1, c2) = line.split_at(line.len() / 2);
-            let (s1, s2) = (
-                c1.chars().collect::<HashSet<char>>(),
-                c2.chars().collect::<HashSet<char>>(),
+pub fn validate(input: &str, group_size: usize) -> Vec<SolveError> {
+    assert!(group_size > 0, "groups need at least one rucksack");
+    let mut errors = Vec::new();
+    // First line, length and shared items of the current group.
+    let mut group = (0, 0, Rucksack::ALL);
+    let check_group = |(first, len, badge): (usize, usize, Rucksack), errors: &mut Vec<_>| {
+        if len < group_size {
+            let reason = format!("Incomplete group: {len} of {group_size} rucksacks");
+            errors.push(SolveError::at_line(first, reason));
+        } else if badge.is_empty() {
+            errors.push(SolveError::at_line(first, "No badge shared by the group"));
+        } else if let Err(e) = badge_priority(first, badge) {
+            errors.push(e);
+        }
+    };
+
+    for (i, line) in normalize(input, Day03::NORMALIZATION).lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((column, item)) = line
+            .chars()
+            .enumerate()
+            .find(|&(_, c)| char_to_priority(c).is_none())
+        {
+            errors.push(
+                SolveError::at_line(i, format!("Invalid item {item:?}"))
+                    .with_column(column + 1, line),
             );
-            let item = s1.intersection(&s2).next()?;
-            char_to_priority(*item)
+        }
+        let len = line.chars().count();
+        if len % 2 == 1 {
+            errors.push(SolveError::at_line(
+                i,
+                format!("Odd number of items: {len}"),
+            ));
+        }
+        if line.is_ascii() {
+            let contents = Contents {
+                line: i,
+                compartments: Rucksack::compartments(line),
+            };
+            let [left, right] = contents.compartments;
+            if (left & right).is_empty() {
+                errors.push(SolveError::at_line(i, "No item in both compartments"));
+            } else if let Err(e) = shared_priority(contents) {
+                errors.push(e);
+            }
+        }
+
+        if group.1 == 0 {
+            group.0 = i;
+        }
+        group.1 += 1;
+        group.2 = group.2 & Rucksack::new(line);
+        if group.1 == group_size {
+            check_group(group, &mut errors);
+            group = (0, 0, Rucksack::ALL);
+        }
+    }
+    if group.1 > 0 {
+        check_group(group, &mut errors);
+    }
+    // Group errors point at the first line of the group but are only found
+    // once it closes.
+    errors.sort_by_key(|e| e.line);
+    errors.into_iter().map(|e| e.for_day(Day03::DAY)).collect()
+}
+
+/// A set of items, stored as one bit per priority, so set operations need
+/// no allocation. Items other than letters have no priority and are left
+/// out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rucksack(u64);
+
+impl Rucksack {
+    /// Every item, from priority 1 to 52.
+    pub const ALL: Self = Self(((1 << 52) - 1) << 1);
+
+    /// The items of `items`.
+    #[must_use]
+    pub fn new(items: &str) -> Self {
+        Self(
+            items
+                .chars()
+                .filter_map(char_to_priority)
+                .fold(0, |mask, priority| mask | 1 << priority),
+        )
+    }
+
+    /// Both halves of `line`, the left one being shorter for odd lengths.
+    #[must_use]
+    pub fn compartments(line: &str) -> [Self; 2] {
+        let (left, right) = line.split_at(line.len() / 2);
+        [Self::new(left), Self::new(right)]
+    }
+
+    #[must_use]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[must_use]
+    pub const fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Priorities of the items, in increasing order.
+    pub fn priorities(self) -> impl Iterator<Item = u64> {
+        let mut mask = self.0;
+        std::iter::from_fn(move || {
+            let priority = (mask != 0).then(|| u64::from(mask.trailing_zeros()))?;
+            mask &= mask - 1;
+            Some(priority)
         })
-        .sum::<u64>()
-        .to_string()
+    }
+
+    #[must_use]
+    pub fn priority_sum(self) -> u64 {
+        self.priorities().sum()
+    }
 }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .collect::<Box<[_]>>()
-        .chunks(3)
-        .filter_map(|lines| {
-            let sets = lines
-                .iter()
-                .map(|line| line.chars().collect::<HashSet<char>>())
-                .collect::<Box<[_]>>();
-            let item = sets
-                .first()?
-                .iter()
-                .find(|c| sets.iter().all(|s| s.contains(c)))?;
-            char_to_priority(*item)
+/// The items, in increasing priority.
+impl Display for Rucksack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.priorities()
+            .map(priority_to_char)
+            .try_for_each(|item| write!(f, "{item}"))
+    }
+}
+
+impl BitAnd for Rucksack {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl BitOr for Rucksack {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// Calls `f` with both compartments of every rucksack of `reader`, skipping
+/// empty lines.
+fn for_each_rucksack(
+    reader: &mut dyn BufRead,
+    mut f: impl FnMut(Contents) -> SolveResult<()>,
+) -> SolveResult<()> {
+    for_each_line(reader, |i, line| {
+        if line.is_empty() {
+            return Ok(());
+        }
+        if !line.is_ascii() {
+            return Err(SolveError::at_line(i, format!("Invalid rucksack: {line}")));
+        }
+        f(Contents {
+            line: i,
+            compartments: Rucksack::compartments(line),
         })
-        .sum::<u64>()
-        .to_string()
+    })
 }
 
 #[must_use]
@@ -51,3 +333,131 @@ fn char_to_priority(c: char) -> Option<u64> {
         _ => return None,
     })
 }
+
+/// Item of priority `priority`, in 1..=52.
+fn priority_to_char(priority: u64) -> char {
+    let base = if priority <= 26 {
+        LOWER_A_DEC - 1
+    } else {
+        UPPER_A_DEC - 27
+    };
+    char::from_u32(u32::try_from(base + priority).unwrap_or_default()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    /// Priority of the first item of `first` found in every one of `others`.
+    fn naive_priority(first: &str, others: &[&str]) -> u64 {
+        let item = first
+            .chars()
+            .find(|&c| others.iter().all(|o| o.contains(c)))
+            .unwrap();
+        ITEMS.find(item).unwrap() as u64 + 1
+    }
+
+    #[test]
+    fn test_rucksack() {
+        let [left, right] = Rucksack::compartments("vJrwpWtwJgWrhcsFMMfFFhFp");
+        assert_eq!(left & right, Rucksack::new("p"));
+        assert_eq!((left | right).len(), 14);
+        assert_eq!(
+            Rucksack::new("aZ!").priorities().collect::<Vec<_>>(),
+            [1, 52]
+        );
+        assert_eq!(Rucksack::ALL.priority_sum(), (1..=52).sum::<u64>());
+        assert!(Rucksack::new("").is_empty());
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let rucksacks = Day03::parse("abcd\naefg\nahij\nbakl\n").unwrap();
+        assert_eq!(badge_sum(&rucksacks, 2), Ok(1 + 1));
+        assert_eq!(badge_sum(&rucksacks, 4), Ok(1));
+        // The last group is the whole of `bakl`.
+        assert_eq!(badge_sum(&rucksacks, 3).unwrap_err().line, Some(4));
+        assert_eq!(
+            badge_sum(&rucksacks, 1).unwrap_err().reason,
+            "Ambiguous badge, the group shares abcd"
+        );
+    }
+
+    #[test]
+    fn test_several_shared_items() {
+        let error = Day03::solve_a("abcA\nabab\n").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.reason, "Several items in both compartments: ab");
+        let stream = Day03::stream_a(&mut "abcA\nabab\n".as_bytes()).unwrap();
+        assert_eq!(stream.unwrap_err().line, error.line);
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                     jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                     PmmdzqPrVvPwwTWBwg\n\
+                     \n\
+                     abcAbc\n\
+                     abcdefg\n\
+                     ab-aCD\n\
+                     xyzXYZ\n";
+        let errors = validate(input, 3)
+            .into_iter()
+            .map(|e| (e.line.unwrap(), e.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                (5, "Several items in both compartments: bc".to_string()),
+                (5, "Ambiguous badge, the group shares ab".to_string()),
+                (6, "Odd number of items: 7".to_string()),
+                (6, "No item in both compartments".to_string()),
+                (7, "Invalid item '-'".to_string()),
+                (8, "No item in both compartments".to_string()),
+                (8, "Incomplete group: 1 of 3 rucksacks".to_string()),
+            ]
+        );
+        let windows = "\u{feff}vJrwpWtwJgWrhcsFMMfFFhFp  \r\n\
+                       jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\r\n\
+                       PmmdzqPrVvPwwTWBwg\t\r\n\r\n";
+        assert_eq!(validate(windows, 3), []);
+        let invalid = validate("ab-aCD\n", 1).remove(0);
+        assert_eq!(invalid.column, Some(3));
+        assert_eq!(
+            invalid.to_string(),
+            "day 3, line 1, column 3: Invalid item '-'\n  ab-aCD\n    ^"
+        );
+        assert_eq!(
+            validate("aA\nbB\n", 2)[1].reason,
+            "No badge shared by the group"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..30_usize) {
+            let input = generate::to_string(Day03::DAY, size, seed).unwrap();
+            let lines = input.lines().collect::<Vec<_>>();
+            let a = lines
+                .iter()
+                .map(|l| {
+                    let (left, right) = l.split_at(l.len() / 2);
+                    naive_priority(left, &[right])
+                })
+                .sum::<u64>();
+            let b = lines
+                .chunks(3)
+                .map(|group| naive_priority(group[0], &group[1..]))
+                .sum::<u64>();
+            prop_assert_eq!(Day03::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day03::solve_b(&input), Ok(b.into()));
+            prop_assert_eq!(Day03::stream_b(&mut input.as_bytes()), Some(Ok(b.into())));
+            prop_assert_eq!(validate(&input, 3), []);
+        }
+    }
+}
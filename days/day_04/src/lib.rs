@@ -1,30 +1,72 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    get_ranges(input)
-        .filter(|(r1, r2)| r1.fully_contains(r2) || r2.fully_contains(r1))
-        .count()
-        .to_string()
+use std::io::BufRead;
+
+use common::{for_each_line, Answer, Solution, SolveError, SolveResult};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+
+    type Input = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        let mut pairs = Vec::new();
+        for_each_pair(&mut input.as_bytes(), |pair| pairs.push(pair))?;
+        Ok(pairs)
+    }
+
+    fn part_a(pairs: &Self::Input) -> SolveResult<Answer> {
+        Ok(pairs.iter().filter(|p| fully_overlap(p)).count().into())
+    }
+
+    fn part_b(pairs: &Self::Input) -> SolveResult<Answer> {
+        Ok(pairs.iter().filter(|p| overlap(p)).count().into())
+    }
+
+    fn stream_a(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut count = 0;
+        Some(
+            for_each_pair(reader, |pair| count += usize::from(fully_overlap(&pair)))
+                .map(|()| count.into()),
+        )
+    }
+
+    fn stream_b(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        let mut count = 0;
+        Some(
+            for_each_pair(reader, |pair| count += usize::from(overlap(&pair)))
+                .map(|()| count.into()),
+        )
+    }
 }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    get_ranges(input)
-        .filter(|(r1, r2)| r1.contains(r2) || r2.contains(r1))
-        .count()
-        .to_string()
+fn fully_overlap((r1, r2): &(Range, Range)) -> bool {
+    r1.fully_contains(r2) || r2.fully_contains(r1)
 }
 
-fn get_ranges(input: &str) -> impl Iterator<Item = (Range, Range)> + '_ {
-    input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let (r1, r2) = line.split_once(',')?;
-            Some((Range::from(r1), Range::from(r2)))
-        })
+fn overlap((r1, r2): &(Range, Range)) -> bool {
+    r1.contains(r2) || r2.contains(r1)
+}
+
+/// Calls `f` with every pair of `reader`, skipping empty lines.
+fn for_each_pair(reader: &mut dyn BufRead, mut f: impl FnMut((Range, Range))) -> SolveResult<()> {
+    for_each_line(reader, |i, line| {
+        if line.is_empty() {
+            return Ok(());
+        }
+        let (r1, r2) = line
+            .split_once(',')
+            .ok_or_else(|| SolveError::at_line(i, format!("invalid pair: {line}")))?;
+        f((
+            Range::try_from(r1).map_err(|e| SolveError::at_line(i, e))?,
+            Range::try_from(r2).map_err(|e| SolveError::at_line(i, e))?,
+        ));
+        Ok(())
+    })
 }
 
-struct Range(u64, u64);
+pub struct Range(u64, u64);
 
 impl Range {
     #[must_use]
@@ -38,12 +80,51 @@ impl Range {
     }
 }
 
-impl From<&str> for Range {
-    fn from(s: &str) -> Self {
-        let (first, second) = s.split_once('-').expect("invalid range");
-        Self(
-            first.parse().expect("invalid range"),
-            second.parse().expect("invalid range"),
-        )
+impl TryFrom<&str> for Range {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let invalid_range = || format!("invalid range: {s}");
+        let (first, second) = s.split_once('-').ok_or_else(invalid_range)?;
+        Ok(Self(
+            first.parse().map_err(|_| invalid_range())?,
+            second.parse().map_err(|_| invalid_range())?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Counts the pairs where one range is a subset of the other, and the
+    /// ones sharing any section, comparing the sets of sections.
+    fn naive_counts(input: &str) -> (usize, usize) {
+        let sections = |range: &str| {
+            let (start, end) = range.split_once('-').unwrap();
+            (start.parse::<u64>().unwrap()..=end.parse().unwrap()).collect::<HashSet<_>>()
+        };
+        input.lines().fold((0, 0), |(a, b), line| {
+            let (r1, r2) = line.split_once(',').unwrap();
+            let (s1, s2) = (sections(r1), sections(r2));
+            (
+                a + usize::from(s1.is_subset(&s2) || s2.is_subset(&s1)),
+                b + usize::from(!s1.is_disjoint(&s2)),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..100_usize) {
+            let input = generate::to_string(Day04::DAY, size, seed).unwrap();
+            let (a, b) = naive_counts(&input);
+            prop_assert_eq!(Day04::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day04::solve_b(&input), Ok(b.into()));
+        }
     }
 }
@@ -1,34 +1,56 @@
 use std::collections::BTreeMap;
 
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let (stacks_input, procedures_input) = input.split_once("\n\n").expect("invalid input");
-    let mut stacks = parse_stacks(stacks_input);
-    let procedures = parse_procedures(procedures_input);
-    stacks.batch_move_crates(&procedures, true);
-
-    stacks.surface_configuration()
-}
+use common::{Answer, Normalization, Solution, SolveError, SolveResult};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Supply Stacks";
+    // The drawing is made of fixed width columns, trailing spaces included.
+    const NORMALIZATION: Normalization = Normalization::LineEndings;
+
+    type Input = (Stacks, Box<[Procedure]>);
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let (stacks_input, procedures_input) = input.split_once("\n\n").expect("invalid input");
-    let mut stacks = parse_stacks(stacks_input);
-    let procedures = parse_procedures(procedures_input);
-    stacks.batch_move_crates(&procedures, false);
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        let (stacks_input, procedures_input) = input
+            .split_once("\n\n")
+            .ok_or("missing blank line between stacks and procedures")?;
+        let procedures_offset = stacks_input.lines().count() + 1;
+        Ok((
+            parse_stacks(stacks_input)?,
+            parse_procedures(procedures_input, procedures_offset)?,
+        ))
+    }
+
+    fn part_a((stacks, procedures): &Self::Input) -> SolveResult<Answer> {
+        let mut stacks = stacks.clone();
+        stacks.batch_move_crates(procedures, true)?;
+
+        Ok(stacks.surface_configuration().into())
+    }
 
-    stacks.surface_configuration()
+    fn part_b((stacks, procedures): &Self::Input) -> SolveResult<Answer> {
+        let mut stacks = stacks.clone();
+        stacks.batch_move_crates(procedures, false)?;
+
+        Ok(stacks.surface_configuration().into())
+    }
 }
 
-struct Stacks {
+#[derive(Clone)]
+pub struct Stacks {
     stacks: BTreeMap<usize, Vec<Crate>>,
 }
 
+#[derive(Clone)]
 struct Crate {
     mark: Box<str>,
 }
 
-struct Procedure {
+pub struct Procedure {
+    /// 0-based line of the procedure in the input.
+    line: usize,
     quantity: usize,
     source: usize,
     destination: usize,
@@ -45,20 +67,28 @@ impl Stacks {
         self.stacks.insert(stack, Vec::new());
     }
 
-    fn add_crate(&mut self, stack: usize, crate_: Crate) {
+    fn add_crate(&mut self, stack: usize, crate_: Crate) -> Result<(), String> {
         let stack = self
             .stacks
             .get_mut(&stack)
-            .unwrap_or_else(|| panic!("stack {stack} does not exist",));
+            .ok_or_else(|| format!("stack {stack} does not exist"))?;
         stack.insert(0, crate_);
+        Ok(())
     }
 
-    fn move_crates(&mut self, procedure: &Procedure, reverse: bool) {
+    fn move_crates(&mut self, procedure: &Procedure, reverse: bool) -> SolveResult<()> {
+        let error = |reason: String| SolveError::at_line(procedure.line, reason);
         let source = self
             .stacks
             .get_mut(&procedure.source)
-            .unwrap_or_else(|| panic!("stack {} does not exist", procedure.source));
+            .ok_or_else(|| error(format!("stack {} does not exist", procedure.source)))?;
         let len = source.len();
+        if procedure.quantity > len {
+            return Err(error(format!(
+                "cannot move {} crates from stack {} with {len} crates",
+                procedure.quantity, procedure.source
+            )));
+        }
         let crates_iter = source.drain(len - procedure.quantity..len);
         let crates = if reverse {
             crates_iter.rev().collect::<Vec<_>>()
@@ -68,14 +98,16 @@ impl Stacks {
         let destination = self
             .stacks
             .get_mut(&procedure.destination)
-            .unwrap_or_else(|| panic!("stack {} does not exist", procedure.destination));
+            .ok_or_else(|| error(format!("stack {} does not exist", procedure.destination)))?;
         destination.extend(crates);
+        Ok(())
     }
 
-    fn batch_move_crates(&mut self, procedures: &[Procedure], reverse: bool) {
+    fn batch_move_crates(&mut self, procedures: &[Procedure], reverse: bool) -> SolveResult<()> {
         for procedure in procedures {
-            self.move_crates(procedure, reverse);
+            self.move_crates(procedure, reverse)?;
         }
+        Ok(())
     }
 
     fn surface_crates_ids(&self) -> Vec<&str> {
@@ -109,11 +141,11 @@ impl TryFrom<String> for Crate {
 // [Z] [M] [P]
 //  1   2   3
 
-fn parse_stacks(stacks_input: &str) -> Stacks {
+fn parse_stacks(stacks_input: &str) -> SolveResult<Stacks> {
     let mut stacks = Stacks::new();
     let last_line_idx = stacks_input
         .rfind('\n')
-        .expect("stacks input must have at least one line");
+        .ok_or("stacks input must have at least one line above the stack ids")?;
     let (raw_stacks, footer) = (
         &stacks_input[..last_line_idx],
         &stacks_input[last_line_idx + 1..],
@@ -123,45 +155,63 @@ fn parse_stacks(stacks_input: &str) -> Stacks {
         stacks.add_stack(stack_id);
     }
 
-    for line in raw_stacks.split_inclusive('\n') {
+    for (line_idx, line) in raw_stacks.split_inclusive('\n').enumerate() {
         for (i, raw_crate) in line.chars().collect::<Box<[char]>>().chunks(4).enumerate() {
-            let Ok(crate_) = Crate::try_from(raw_crate.iter().collect::<String>()) else { continue };
-            stacks.add_crate(i + 1, crate_);
+            let Ok(crate_) = Crate::try_from(raw_crate.iter().collect::<String>()) else {
+                continue;
+            };
+            stacks
+                .add_crate(i + 1, crate_)
+                .map_err(|e| SolveError::at_line(line_idx, e))?;
         }
     }
 
-    stacks
+    Ok(stacks)
 }
 
-fn parse_procedures(procedures_input: &str) -> Box<[Procedure]> {
+fn parse_procedures(procedures_input: &str, offset: usize) -> SolveResult<Box<[Procedure]>> {
     procedures_input
         .split('\n')
-        .filter_map(|line| {
-            if line.trim().is_empty() {
-                return None;
-            }
-            Some(Procedure::from(line))
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            Procedure::try_from(line)
+                .map(|procedure| Procedure {
+                    line: i + offset,
+                    ..procedure
+                })
+                .map_err(|e| SolveError::at_line(i, e).offset_line(offset))
         })
         .collect()
 }
 
-impl From<&str> for Procedure {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Procedure {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let mut values = s
             .split_whitespace()
             .skip(1)
             .step_by(2)
             .filter_map(|s| s.parse().ok());
-        Self {
-            quantity: values.next().expect("invalid procedure quantity"),
-            source: values.next().expect("invalid procedure source"),
-            destination: values.next().expect("invalid procedure destination"),
-        }
+        let mut next = |field: &str| {
+            values
+                .next()
+                .ok_or_else(|| format!("invalid procedure {field}: {s}"))
+        };
+        Ok(Self {
+            line: 0,
+            quantity: next("quantity")?,
+            source: next("source")?,
+            destination: next("destination")?,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -170,7 +220,53 @@ mod tests {
 [N] [C]    
 [Z] [M] [P]
  1   2   3 "#;
-        let stacks = parse_stacks(stacks_input);
+        let stacks = parse_stacks(stacks_input).expect("valid stacks");
         assert_eq!(stacks.surface_configuration(), "NDP");
     }
+
+    /// Moves the crates of the drawing one by one, or all at once.
+    fn naive_tops(input: &str, one_by_one: bool) -> String {
+        let (drawing, procedures) = input.split_once("\n\n").unwrap();
+        let mut rows = drawing.lines().rev();
+        let count = rows.next().unwrap().split_whitespace().count();
+        let mut stacks = vec![Vec::new(); count];
+        for row in rows {
+            for (i, stack) in stacks.iter_mut().enumerate() {
+                match row.as_bytes().get(1 + 4 * i) {
+                    Some(&c) if c != b' ' => stack.push(c),
+                    _ => {}
+                }
+            }
+        }
+        for procedure in procedures.lines() {
+            let numbers = procedure
+                .split(' ')
+                .filter_map(|n| n.parse::<usize>().ok())
+                .collect::<Vec<_>>();
+            let [quantity, source, destination] = numbers[..] else {
+                panic!("invalid procedure {procedure}");
+            };
+            let mut moved = Vec::new();
+            for _ in 0..quantity {
+                moved.push(stacks[source - 1].pop().unwrap());
+            }
+            if !one_by_one {
+                moved.reverse();
+            }
+            stacks[destination - 1].extend(moved);
+        }
+        stacks
+            .iter()
+            .filter_map(|s| s.last().map(|&c| char::from(c)))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 1..60_usize) {
+            let input = generate::to_string(Day05::DAY, size, seed).unwrap();
+            prop_assert_eq!(Day05::solve_a(&input), Ok(naive_tops(&input, true).into()));
+            prop_assert_eq!(Day05::solve_b(&input), Ok(naive_tops(&input, false).into()));
+        }
+    }
 }
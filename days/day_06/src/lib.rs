@@ -1,55 +1,127 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    get_first_marker_pos(input, 4)
-}
+use std::io::BufRead;
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    get_first_marker_pos(input, 14)
-}
+use common::{skip_bom, Answer, Solution, SolveError, SolveResult};
 
-fn get_first_marker_pos(input: &str, marker_size: usize) -> String {
-    let (_, idxs) = input.chars().enumerate().fold(
-        (Vec::with_capacity(marker_size), Vec::new()),
-        |(mut acc, mut idxs), (i, c)| {
-            if let Some(idx) = acc.iter().position(|c2| *c2 == c) {
-                if idx == acc.len() - 1 {
-                    acc.truncate(0);
-                } else {
-                    acc = acc.split_off(idx + 1);
-                }
-            }
-            acc.push(c);
-            if acc.len() == marker_size {
-                idxs.push(i + 1);
-                acc.truncate(0);
-            }
+pub struct Day06;
 
-            (acc, idxs)
-        },
-    );
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
 
-    idxs.first()
-        .map_or_else(|| "No marker found".to_string(), ToString::to_string)
+    type Input = String;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part_a(input: &Self::Input) -> SolveResult<Answer> {
+        get_first_marker_pos(&mut input.as_bytes(), 4)
+    }
+
+    fn part_b(input: &Self::Input) -> SolveResult<Answer> {
+        get_first_marker_pos(&mut input.as_bytes(), 14)
+    }
+
+    fn stream_a(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        Some(get_first_marker_pos(reader, 4))
+    }
+
+    fn stream_b(reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        Some(get_first_marker_pos(reader, 14))
+    }
+}
+
+/// Number of bytes read until the last `marker_size` were all different.
+/// Reading stops there, so the stream may be unbounded.
+fn get_first_marker_pos(reader: &mut dyn BufRead, marker_size: usize) -> SolveResult<Answer> {
+    // 1-based position of the last occurrence of every byte, 0 if none yet.
+    let mut last_seen = [0; 256];
+    // Position of the byte before the current run of different bytes.
+    let mut start = 0;
+    let mut pos = 0;
+    skip_bom(reader).map_err(|e| SolveError::new(format!("failed to read input: {e}")))?;
+    loop {
+        let buf = reader
+            .fill_buf()
+            .map_err(|e| SolveError::new(format!("failed to read input: {e}")))?;
+        if buf.is_empty() {
+            return Err("No marker found".into());
+        }
+        for &byte in buf {
+            pos += 1;
+            start = start.max(last_seen[usize::from(byte)]);
+            last_seen[usize::from(byte)] = pos;
+            if pos - start == marker_size {
+                return Ok(pos.into());
+            }
+        }
+        let len = buf.len();
+        reader.consume(len);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        collections::HashSet,
+        io::{repeat, BufReader, Read},
+    };
+
+    use proptest::prelude::*;
+
     use super::*;
 
+    /// Checks every window from the start, one by one.
+    fn naive_marker(input: &str, marker_size: usize) -> Option<Answer> {
+        input
+            .as_bytes()
+            .windows(marker_size)
+            .position(|w| w.iter().collect::<HashSet<_>>().len() == marker_size)
+            .map(|i| (i + marker_size).into())
+    }
+
     #[test]
-    fn test_calc_b() {
+    fn test_part_b() {
         let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        let expected = "19";
-        let actual = calc_b(input);
+        let expected = Ok(19.into());
+        let actual = Day06::solve_b(input);
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn test_calc_a() {
+    fn test_part_a() {
         let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        let expected = "7";
-        let actual = calc_a(input);
+        let expected = Ok(7.into());
+        let actual = Day06::solve_a(input);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_stream() {
+        let long = repeat(b'a')
+            .take(1 << 20)
+            .chain("bcdefghijklmnopq".as_bytes());
+        let expected = Some(Ok(((1 << 20) + 13).into()));
+        assert_eq!(Day06::stream_b(&mut BufReader::new(long)), expected);
+
+        let unbounded = "mjqjpqmgbljsphdztnvjfqwrcgsmlb"
+            .as_bytes()
+            .chain(repeat(b'a'));
+        let expected = Some(Ok(19.into()));
+        assert_eq!(Day06::stream_b(&mut BufReader::new(unbounded)), expected);
+
+        let no_marker = repeat(b'x').take(100);
+        let expected = Some(Err("No marker found".into()));
+        assert_eq!(Day06::stream_a(&mut BufReader::new(no_marker)), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(input in "[a-p]{0,200}") {
+            prop_assert_eq!(Day06::solve_a(&input).ok(), naive_marker(&input, 4));
+            prop_assert_eq!(Day06::solve_b(&input).ok(), naive_marker(&input, 14));
+            let streamed = Day06::stream_b(&mut input.as_bytes()).and_then(Result::ok);
+            prop_assert_eq!(streamed, naive_marker(&input, 14));
+        }
+    }
 }
@@ -1,41 +1,56 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, ops::AddAssign, path::PathBuf};
-
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let (_, folders) = get_files(input);
-    folders
-        .into_iter()
-        .filter(|(_, size)| *size < 100_000)
-        .map(|(_, size)| size)
-        .sum::<usize>()
-        .to_string()
-}
+use std::{collections::HashMap, path::PathBuf};
+
+use common::{Answer, Solution, SolveError, SolveResult};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
+
+    type Input = HashMap<PathBuf, usize>;
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let goal = 40_000_000_usize;
-    let (_, folders) = get_files(input);
-    let used_space = folders.get(&PathBuf::from("/")).expect("No root folder");
-    if *used_space < goal {
-        "0".to_string()
-    } else {
-        let to_remove = used_space - goal;
-        let mut folders = folders
-            .into_iter()
-            .filter(|(_, size)| *size > to_remove)
-            .map(|(_, size)| size)
-            .collect::<Vec<_>>();
-        folders.sort_unstable();
-        folders.first().expect("Empty folders").to_string()
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        let (_, folders) = get_files(input)?;
+        Ok(folders)
+    }
+
+    fn part_a(folders: &Self::Input) -> SolveResult<Answer> {
+        Ok(folders
+            .values()
+            .filter(|size| **size < 100_000)
+            .sum::<usize>()
+            .into())
+    }
+
+    fn part_b(folders: &Self::Input) -> SolveResult<Answer> {
+        let goal = 40_000_000_usize;
+        // Without any file the root folder is empty.
+        let used_space = folders.get(&PathBuf::from("/")).copied().unwrap_or(0);
+        if used_space < goal {
+            Ok(0_usize.into())
+        } else {
+            let to_remove = used_space - goal;
+            let mut folders = folders
+                .values()
+                .filter(|size| **size > to_remove)
+                .collect::<Vec<_>>();
+            folders.sort_unstable();
+            folders
+                .first()
+                .map(|&&size| size.into())
+                .ok_or_else(|| "Empty folders".into())
+        }
     }
 }
 
-#[allow(clippy::option_if_let_else)]
-fn get_files(input: &str) -> (Vec<(PathBuf, usize)>, HashMap<PathBuf, usize>) {
+type Files = Vec<(PathBuf, usize)>;
+
+fn get_files(input: &str) -> SolveResult<(Files, HashMap<PathBuf, usize>)> {
     let mut files = Vec::new();
-    let commands = parse_commands(input).skip(1).collect::<Vec<_>>();
+    let commands = parse_commands(input)?;
     let mut current_dir = PathBuf::from("/");
     for command in commands {
         match command {
@@ -60,27 +75,38 @@ fn get_files(input: &str) -> (Vec<(PathBuf, usize)>, HashMap<PathBuf, usize>) {
         let mut path = path.clone();
         while let Some(parent) = path.parent() {
             path = parent.to_path_buf();
-            if let Some(f) = folders.get_mut(&path) {
-                f.add_assign(size);
-            } else {
-                folders.insert(path.clone(), *size);
-            }
+            let folder = folders.entry(path.clone()).or_default();
+            *folder = folder
+                .checked_add(*size)
+                .ok_or_else(|| format!("Folder too large: {}", path.display()))?;
         }
     }
 
-    (files, folders)
+    Ok((files, folders))
 }
 
-fn parse_commands(input: &str) -> impl Iterator<Item = Command<'_>> + '_ {
-    input
-        .trim_matches(['\n', '$', ' '].as_slice())
-        .split("\n$ ")
-        .filter_map(|line| Command::try_from(line).ok())
+/// Parses the terminal transcript, attaching every output line to the `ls`
+/// command above it.
+fn parse_commands(input: &str) -> SolveResult<Vec<Command<'_>>> {
+    let mut commands = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(command) = line.strip_prefix("$ ") {
+            commands.push(Command::try_from(command).map_err(|e| SolveError::at_line(i, e))?);
+        } else if let Some(Command::Ls { content }) = commands.last_mut() {
+            content.push(RawDirEntry::try_from(line).map_err(|e| SolveError::at_line(i, e))?);
+        } else {
+            return Err(SolveError::at_line(i, format!("Output without ls: {line}")));
+        }
+    }
+    Ok(commands)
 }
 
 enum Command<'a> {
     Cd { path: &'a str },
-    Ls { content: Box<[RawDirEntry<'a>]> },
+    Ls { content: Vec<RawDirEntry<'a>> },
 }
 
 enum RawDirEntry<'a> {
@@ -91,27 +117,88 @@ enum RawDirEntry<'a> {
 impl<'a> TryFrom<&'a str> for Command<'a> {
     type Error = String;
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
-        match &s[..2] {
-            "cd" => Ok(Command::Cd { path: &s[3..] }),
-            "ls" => Ok(Command::Ls {
-                content: s[3..].split('\n').map(RawDirEntry::from).collect(),
+        match s.split_once(' ') {
+            Some(("cd", path)) => Ok(Command::Cd { path }),
+            None if s == "ls" => Ok(Command::Ls {
+                content: Vec::new(),
             }),
-            cmd => Err(format!("Invalid command: {cmd}")),
+            _ => Err(format!("Invalid command: {s}")),
         }
     }
 }
 
-impl<'a> From<&'a str> for RawDirEntry<'a> {
-    fn from(s: &'a str) -> Self {
-        if &s[..3] == "dir" {
-            RawDirEntry::Dir { name: &s[4..] }
+impl<'a> TryFrom<&'a str> for RawDirEntry<'a> {
+    type Error = String;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        if let Some(name) = s.strip_prefix("dir ") {
+            Ok(RawDirEntry::Dir { name })
         } else {
-            let (raw_size, name) = s.split_once(' ').expect("Invalid file entry");
+            let (raw_size, name) = s
+                .split_once(' ')
+                .ok_or_else(|| format!("Invalid file entry: {s}"))?;
 
-            RawDirEntry::File {
+            Ok(RawDirEntry::File {
                 name,
-                size: raw_size.parse().expect("Invalid file size"),
+                size: raw_size
+                    .parse()
+                    .map_err(|e| format!("Invalid file size {raw_size:?}: {e}"))?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_folder_too_large() {
+        let input = "$ cd /\n$ ls\n18446744073709551615 a\n1 b\n";
+        assert!(Day07::parse(input).is_err());
+    }
+
+    #[test]
+    fn test_nothing_to_free() {
+        assert_eq!(Day07::solve_b("$ cd /\n$ ls\n1 a\n"), Ok(0.into()));
+    }
+
+    /// Sizes of every folder, adding each file to all the folders above it.
+    fn naive_sizes(input: &str) -> HashMap<String, usize> {
+        let mut cwd = Vec::new();
+        let mut sizes = HashMap::new();
+        for line in input.lines() {
+            match line.split(' ').collect::<Vec<_>>()[..] {
+                ["$", "cd", "/"] => cwd.clear(),
+                ["$", "cd", ".."] => drop(cwd.pop()),
+                ["$", "cd", name] => cwd.push(name),
+                ["$", "ls"] | ["dir", _] => {}
+                [size, _] => {
+                    for depth in 0..=cwd.len() {
+                        *sizes.entry(cwd[..depth].join("/")).or_default() +=
+                            size.parse::<usize>().unwrap();
+                    }
+                }
+                _ => panic!("invalid line {line}"),
             }
         }
+        sizes
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 1..60_usize) {
+            let input = generate::to_string(Day07::DAY, size, seed).unwrap();
+            let sizes = naive_sizes(&input);
+            let a = sizes.values().filter(|&&s| s < 100_000).sum::<usize>();
+            let to_free = sizes.get("").map_or(0, |s| s.saturating_sub(40_000_000));
+            let b = match sizes.values().filter(|&&s| s > to_free).min() {
+                Some(&size) if to_free > 0 => Answer::from(size),
+                _ => Answer::from(0_usize),
+            };
+            prop_assert_eq!(Day07::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day07::solve_b(&input), Ok(b));
+        }
     }
 }
@@ -1,137 +1,145 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let matrix = Matrix::from(input);
-    matrix.visible_count().to_string()
-}
+use common::{Answer, Solution, SolveError, SolveResult};
+use grid::{Grid, Point};
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let matrix = Matrix::from(input);
-    matrix.max_scenic_score().to_string()
-}
+pub struct Day08;
 
-struct Matrix(Box<[Box<[u32]>]>);
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Treetop Tree House";
 
-impl Matrix {
-    fn visible_count(&self) -> usize {
-        let (rows, cols) = self.size();
-        let mut count = 0;
-        for row in 0..rows {
-            for col in 0..cols {
-                let v = self.0[row][col];
-                count += usize::from(
-                    row == 0
-                        || col == 0
-                        || row == rows - 1
-                        || col == cols - 1
-                        || self.row_slice_is_less(row, v, 0, col)
-                        || self.row_slice_is_less(row, v, col + 1, cols)
-                        || self.get_col_iter(col, v, 0, row)
-                        || self.get_col_iter(col, v, row + 1, rows),
-                );
-            }
-        }
-        count
-    }
+    type Input = Matrix;
 
-    fn max_scenic_score(&self) -> usize {
-        let (rows, cols) = self.size();
-        let mut max_score = 0;
-        for row in 0..rows {
-            for col in 0..cols {
-                if row == 0 || col == 0 || row == rows - 1 || col == cols - 1 {
-                    continue;
-                }
-
-                let v = self.0[row][col];
-                let mut score = 1;
-                score *= self.row_reach(row, v, 0, col, true);
-                score *= self.row_reach(row, v, col + 1, cols, false);
-                score *= self.col_reach(col, v, 0, row, true);
-                score *= self.col_reach(col, v, row + 1, rows, false);
-
-                if score > max_score {
-                    max_score = score;
-                }
-            }
-        }
-        max_score
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        Matrix::try_from(input)
     }
 
-    fn size(&self) -> (usize, usize) {
-        (self.0.len(), self.0.first().map_or(0, |row| row.len()))
+    fn part_a(matrix: &Self::Input) -> SolveResult<Answer> {
+        Ok(matrix.visible_count().into())
     }
 
-    fn row_slice_is_less(&self, row: usize, value: u32, start: usize, end: usize) -> bool {
-        self.0[row][start..end].iter().all(|&x| x < value)
+    fn part_b(matrix: &Self::Input) -> SolveResult<Answer> {
+        Ok(matrix.max_scenic_score().into())
     }
+}
 
-    fn row_reach(&self, row: usize, value: u32, start: usize, end: usize, reverse: bool) -> usize {
-        let slice = self.0[row][start..end].iter();
-        get_reach(
-            value,
-            if reverse {
-                slice.rev().collect()
-            } else {
-                slice.collect()
-            },
-        )
+pub struct Matrix(Grid<u32>);
+
+impl Matrix {
+    fn visible_count(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|&(p, &v)| {
+                self.sight_lines(p)
+                    .into_iter()
+                    .any(|mut line| line.all(|&x| x < v))
+            })
+            .count()
     }
 
-    fn get_col_iter(&self, col: usize, value: u32, start: usize, end: usize) -> bool {
-        self.0[start..end]
+    fn max_scenic_score(&self) -> usize {
+        self.0
             .iter()
-            .map(move |row| &row[col])
-            .all(|&x| x < value)
+            .map(|(p, &v)| {
+                self.sight_lines(p)
+                    .into_iter()
+                    .map(|line| get_reach(v, line))
+                    .product()
+            })
+            .max()
+            .unwrap_or(0)
     }
 
-    fn col_reach(&self, col: usize, value: u32, start: usize, end: usize, reverse: bool) -> usize {
-        let slice = self.0[start..end].iter().map(move |row| &row[col]);
-        get_reach(
-            value,
-            if reverse {
-                slice.rev().collect()
-            } else {
-                slice.collect()
-            },
-        )
+    /// Trees to the left, right, top and bottom of `p`, walking away from it.
+    fn sight_lines(&self, p: Point) -> [Box<dyn Iterator<Item = &u32> + '_>; 4] {
+        let row = self.0.row(p.y);
+        [
+            Box::new(row[..p.x].iter().rev()),
+            Box::new(row[p.x + 1..].iter()),
+            Box::new(self.0.column(p.x).take(p.y).rev()),
+            Box::new(self.0.column(p.x).skip(p.y + 1)),
+        ]
     }
 }
 
-fn get_reach(value: u32, arr: Vec<&u32>) -> usize {
+/// Trees seen from a tree of height `value` looking along `trees`.
+fn get_reach<'a>(value: u32, trees: impl Iterator<Item = &'a u32>) -> usize {
     let mut count = 0;
-    for v in arr {
+    for &v in trees {
         count += 1;
 
-        if *v >= value {
+        if v >= value {
             break;
         }
     }
     count
 }
 
-impl From<&str> for Matrix {
-    fn from(input: &str) -> Self {
-        let matrix = input
-            .lines()
-            .map(|line| line.chars().filter_map(|c| c.to_digit(10)).collect())
-            .collect();
-        Self(matrix)
+impl TryFrom<&str> for Matrix {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Grid::parse(input, |c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("Invalid tree height: {c:?}"))
+        })
+        .map(Self)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
     #[test]
-    fn test_calc_b() {
+    fn test_part_b() {
         let input = r#"30373
 25512
 65332
 33549
 35390"#;
-        assert_eq!(calc_b(input), "8");
+        assert_eq!(Day08::solve_b(input), Ok(8.into()));
+    }
+
+    /// Walks from every tree in the 4 directions over the raw digits.
+    fn naive_scan(input: &str) -> (usize, usize) {
+        let rows = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let (height, width) = (rows.len() as i64, rows[0].len() as i64);
+        let (mut visible, mut best) = (0, 0);
+        for y in 0..height {
+            for x in 0..width {
+                let tree = rows[y as usize][x as usize];
+                let (mut is_visible, mut score) = (false, 1);
+                for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+                    let (mut nx, mut ny, mut distance) = (x + dx, y + dy, 0);
+                    let mut blocked = false;
+                    while (0..width).contains(&nx) && (0..height).contains(&ny) {
+                        distance += 1;
+                        if rows[ny as usize][nx as usize] >= tree {
+                            blocked = true;
+                            break;
+                        }
+                        nx += dx;
+                        ny += dy;
+                    }
+                    is_visible |= !blocked;
+                    score *= distance;
+                }
+                visible += usize::from(is_visible);
+                best = best.max(score);
+            }
+        }
+        (visible, best)
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 1..900_usize) {
+            let input = generate::to_string(Day08::DAY, size, seed).unwrap();
+            let (visible, best) = naive_scan(&input);
+            prop_assert_eq!(Day08::solve_a(&input), Ok(visible.into()));
+            prop_assert_eq!(Day08::solve_b(&input), Ok(best.into()));
+        }
     }
 }
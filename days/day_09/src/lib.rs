@@ -1,30 +1,53 @@
 use std::collections::{HashMap, HashSet};
 
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    get_visited_cords_count::<1>(input)
-}
+use common::{Answer, Solution, SolveError, SolveResult};
+use grid::SignedPoint;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Rope Bridge";
+
+    type Input = Vec<KnotMove>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        parse_moves(input)
+    }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    get_visited_cords_count::<9>(input)
+    fn part_a(moves: &Self::Input) -> SolveResult<Answer> {
+        Ok(get_visited_cords_count::<1>(moves).into())
+    }
+
+    fn part_b(moves: &Self::Input) -> SolveResult<Answer> {
+        Ok(get_visited_cords_count::<9>(moves).into())
+    }
 }
 
-fn get_visited_cords_count<const TAIL_LEN: usize>(input: &str) -> String {
-    let grid = get_grid::<TAIL_LEN>(input);
+fn get_visited_cords_count<const TAIL_LEN: usize>(moves: &[KnotMove]) -> usize {
+    let grid = grid_from_moves::<TAIL_LEN>(moves);
     let visited_cords = grid.get_last_knot_visited_cords();
-    visited_cords.len().to_string()
+    visited_cords.len()
 }
 
-#[must_use]
-pub fn get_grid<const TAIL_LEN: usize>(input: &str) -> Grid<TAIL_LEN> {
-    let mut grid = Grid::<TAIL_LEN>::new();
-    let moves = input
+/// # Errors
+/// If a move is malformed.
+pub fn get_grid<const TAIL_LEN: usize>(input: &str) -> SolveResult<Grid<TAIL_LEN>> {
+    Ok(grid_from_moves(&parse_moves(input)?))
+}
+
+fn parse_moves(input: &str) -> SolveResult<Vec<KnotMove>> {
+    input
         .lines()
-        .filter(|s| !s.is_empty())
-        .filter_map(|s| KnotMove::try_from(s).ok())
-        .collect::<Vec<_>>();
-    for km in &moves {
+        .enumerate()
+        .filter(|(_, s)| !s.is_empty())
+        .map(|(i, s)| KnotMove::try_from(s).map_err(|e| SolveError::at_line(i, e)))
+        .collect()
+}
+
+fn grid_from_moves<const TAIL_LEN: usize>(moves: &[KnotMove]) -> Grid<TAIL_LEN> {
+    let mut grid = Grid::<TAIL_LEN>::new();
+    for km in moves {
         grid.move_rope(km);
     }
     grid
@@ -46,16 +69,10 @@ struct Rope<const TAIL_LEN: usize> {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Knot(Cord);
-
-#[derive(Default, Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Cord {
-    x: i32,
-    y: i32,
-}
+struct Knot(SignedPoint);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct KnotMove {
+pub struct KnotMove {
     direction: Direction,
     distance: usize,
 }
@@ -76,13 +93,13 @@ impl<const TAIL_LEN: usize> Grid<TAIL_LEN> {
         }
     }
 
-    fn get_last_knot_visited_cords(&self) -> HashSet<Cord> {
-        let mut visited_cords = HashSet::from_iter([Cord { x: 0, y: 0 }]);
+    fn get_last_knot_visited_cords(&self) -> HashSet<SignedPoint> {
+        let mut visited_cords = HashSet::from_iter([SignedPoint::default()]);
         for (_, states) in &self.states_map {
             for state in states {
                 let Some(last_knot) = state.rope.tail.last() else {
-                continue;
-            };
+                    continue;
+                };
                 visited_cords.insert(last_knot.0);
             }
         }
@@ -120,7 +137,7 @@ impl<const TAIL_LEN: usize> Grid<TAIL_LEN> {
 
         for y in (min_y..=max_y).rev() {
             for x in min_x..=max_x {
-                let cord = Cord { x, y };
+                let cord = SignedPoint::new(x, y);
                 if x == 0 && y == 0 {
                     buf.push('s');
                 } else if visited_cords.contains(&cord) {
@@ -136,51 +153,13 @@ impl<const TAIL_LEN: usize> Grid<TAIL_LEN> {
     }
 
     /// Returns the min and max x and y cords of the grid.
-    fn grid_points(&self) -> (Cord, Cord) {
-        let x_cords = self
-            .states_map
-            .iter()
-            .flat_map(|(_, states)| {
-                states.iter().flat_map(|state| {
-                    state
-                        .rope
-                        .tail
-                        .iter()
-                        .map(|knot| knot.0.x)
-                        .chain(std::iter::once(state.rope.head.0.x))
-                })
-            })
-            .collect::<Box<[_]>>();
-        let y_cords = self
-            .states_map
-            .iter()
-            .flat_map(|(_, states)| {
-                states.iter().flat_map(|state| {
-                    state
-                        .rope
-                        .tail
-                        .iter()
-                        .map(|knot| knot.0.y)
-                        .chain(std::iter::once(state.rope.head.0.y))
-                })
+    fn grid_points(&self) -> (SignedPoint, SignedPoint) {
+        let knots = self.states_map.iter().flat_map(|(_, states)| {
+            states.iter().flat_map(|state| {
+                std::iter::once(state.rope.head.0).chain(state.rope.tail.iter().map(|knot| knot.0))
             })
-            .collect::<Box<[_]>>();
-
-        let min_x = x_cords.iter().min().expect("No x cords");
-        let max_x = x_cords.iter().max().expect("No x cords");
-        let min_y = y_cords.iter().min().expect("No y cords");
-        let max_y = y_cords.iter().max().expect("No y cords");
-
-        (
-            Cord {
-                x: *min_x,
-                y: *min_y,
-            },
-            Cord {
-                x: *max_x,
-                y: *max_y,
-            },
-        )
+        });
+        SignedPoint::bounds(knots).expect("No knots")
     }
 }
 
@@ -211,22 +190,22 @@ impl<const TAIL_LEN: usize> Rope<TAIL_LEN> {
 
 impl Knot {
     const fn new() -> Self {
-        Self(Cord { x: 0, y: 0 })
+        Self(SignedPoint::new(0, 0))
     }
     /// Returns true if the knot moved.
     /// # Panics
     /// If the knot cannot move.
     fn follow_up(&mut self, other: Self) {
-        if self.0.step_distance(other.0) <= 1 {
+        if self.0.chebyshev_distance(other.0) <= 1 {
             return;
         }
         self.0 = self
             .0
-            .surrounding()
+            .neighbours8()
             .into_iter()
             .fold(self.0, |mut saved_cord, cord| {
-                let d1 = cord.distance(other.0);
-                let d2 = saved_cord.distance(other.0);
+                let d1 = distance(cord, other.0);
+                let d2 = distance(saved_cord, other.0);
                 if d1 + f64::EPSILON < d2 {
                     saved_cord = cord;
                 }
@@ -244,72 +223,16 @@ impl Knot {
     }
 }
 
-impl Cord {
-    fn distance(self, other: Self) -> f64 {
-        let x_diff = f64::from((self.x - other.x).abs());
-        let y_diff = f64::from((self.y - other.y).abs());
-        x_diff.hypot(y_diff)
-    }
-
-    fn step_distance(self, other: Self) -> i32 {
-        let mut point = self;
-        let mut steps = 0;
-        while point != other {
-            let mut distance = other.distance(point);
-            for possible_cord in point.surrounding() {
-                let d = other.distance(possible_cord);
-                if d + f64::EPSILON < distance {
-                    distance = d;
-                    point = possible_cord;
-                }
-            }
-            steps += 1;
-        }
-
-        steps
-    }
-    const fn surrounding(self) -> [Self; 8] {
-        [
-            Self {
-                x: self.x - 1,
-                y: self.y - 1,
-            },
-            Self {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Self {
-                x: self.x + 1,
-                y: self.y - 1,
-            },
-            Self {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Self {
-                x: self.x + 1,
-                y: self.y + 1,
-            },
-            Self {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Self {
-                x: self.x - 1,
-                y: self.y + 1,
-            },
-            Self {
-                x: self.x - 1,
-                y: self.y,
-            },
-        ]
-    }
+fn distance(a: SignedPoint, b: SignedPoint) -> f64 {
+    let x_diff = (a.x - b.x).abs() as f64;
+    let y_diff = (a.y - b.y).abs() as f64;
+    x_diff.hypot(y_diff)
 }
 
 impl KnotMove {
     /// Returns the visualization of the knot move.
     /// # Example
-    /// ```
+    /// ```text
     /// == U 1 ==
     ///
     /// ```
@@ -327,19 +250,19 @@ impl KnotMove {
 #[inline]
 /// Draws the vizualization of a row.
 /// # Example
-/// ```
+/// ```text
 /// 1H....  ( 1 convers s )
 /// ```
 fn draw_row(
     visualization: &mut String,
-    min_x: i32,
-    max_x: i32,
-    y: i32,
-    cords: &HashMap<(i32, i32), Vec<char>>,
+    min_x: i64,
+    max_x: i64,
+    y: i64,
+    cords: &HashMap<SignedPoint, Vec<char>>,
 ) {
     let mut covereds = Vec::new();
     for x in min_x..=max_x {
-        if let Some(knots) = cords.get(&(x, y)) {
+        if let Some(knots) = cords.get(&SignedPoint::new(x, y)) {
             visualization.push(*knots.first().expect("No knot found"));
             if knots.len() > 1 {
                 covereds.push(knots.as_slice());
@@ -356,7 +279,7 @@ fn draw_row(
 #[inline]
 /// Draws the covered points in the visualization.
 /// Example
-/// ```
+/// ```text
 ///   ( A convers B, C, D ; E convers F, G )
 /// ```
 fn draw_convered_points(visualization: &mut String, covereds: Vec<&[char]>) {
@@ -384,26 +307,26 @@ impl<const TAIL_LEN: usize> GridState<TAIL_LEN> {
     #[inline]
     /// Returns the visualization of the grid state.
     /// # Example
-    /// ```
+    /// ```text
     /// ......
     /// ......
     /// ......
     /// ......
     /// 1H....  ( 1 convers s )
     /// ```
-    fn get_visualization(&self, (min_cord, max_cord): (Cord, Cord)) -> String {
+    fn get_visualization(&self, (min_cord, max_cord): (SignedPoint, SignedPoint)) -> String {
         let (min_x, min_y) = (min_cord.x, min_cord.y);
         let (max_x, max_y) = (max_cord.x, max_cord.y);
 
-        let mut cords = HashMap::<(i32, i32), Vec<char>>::new();
-        cords.entry(self.rope.head.0.into()).or_default().push('H');
+        let mut cords = HashMap::<SignedPoint, Vec<char>>::new();
+        cords.entry(self.rope.head.0).or_default().push('H');
         for (i, knot) in self.rope.tail.iter().enumerate() {
             cords
-                .entry(knot.0.into())
+                .entry(knot.0)
                 .or_default()
                 .push(char::from_digit(i as u32 + 1, 16).expect("Invalid digit"));
         }
-        cords.entry((0, 0)).or_default().push('s');
+        cords.entry(SignedPoint::default()).or_default().push('s');
 
         let mut visualization = String::new();
         for y in (min_y..=max_y).rev() {
@@ -419,9 +342,14 @@ impl TryFrom<&str> for KnotMove {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (direction, distance) = value
+            .split_once(' ')
+            .ok_or_else(|| format!("invalid move: {value}"))?;
         Ok(Self {
-            direction: Direction::try_from(&value[0..1])?,
-            distance: value[2..].parse::<usize>().map_err(|e| e.to_string())?,
+            direction: Direction::try_from(direction)?,
+            distance: distance
+                .parse::<usize>()
+                .map_err(|e| format!("invalid distance {distance:?}: {e}"))?,
         })
     }
 }
@@ -445,22 +373,19 @@ impl<const TAIL_LEN: usize> From<Rope<TAIL_LEN>> for GridState<TAIL_LEN> {
         Self { rope }
     }
 }
-impl From<Cord> for (i32, i32) {
-    fn from(cord: Cord) -> Self {
-        (cord.x, cord.y)
-    }
-}
 
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used)]
+    use proptest::prelude::*;
+
     use super::*;
     const TEST_INPUT: &str = include_str!("../../../inputs/day_09/test_input.txt");
 
     #[test]
     #[ignore]
     fn test_visualization_1() {
-        let grid = get_grid::<1>(TEST_INPUT);
+        let grid = get_grid::<1>(TEST_INPUT).unwrap();
         let visualization = grid.get_visualization();
         std::fs::write("test_visualization_1.txt", visualization).unwrap();
     }
@@ -468,18 +393,18 @@ mod tests {
     #[test]
     #[ignore]
     fn test_visualization_2() {
-        let grid = get_grid::<9>(TEST_INPUT);
+        let grid = get_grid::<9>(TEST_INPUT).unwrap();
         let visualization = grid.get_visualization();
         std::fs::write("test_visualization_2.txt", visualization).unwrap();
     }
 
     #[test]
-    fn test_calc_a() {
-        assert_eq!(calc_a(TEST_INPUT), "13");
+    fn test_part_a() {
+        assert_eq!(Day09::solve_a(TEST_INPUT).unwrap(), 13.into());
     }
 
     #[test]
-    fn test_calc_b() {
+    fn test_part_b() {
         let input = r#"R 5
 U 8
 L 8
@@ -490,6 +415,41 @@ L 25
 U 20
 "#;
 
-        assert_eq!(calc_b(input), "36");
+        assert_eq!(Day09::solve_b(input).unwrap(), 36.into());
+    }
+
+    /// Pulls a rope of `knots` knots one step at a time.
+    fn naive_tail_visits(input: &str, knots: usize) -> usize {
+        let mut rope = vec![(0_i64, 0_i64); knots];
+        let mut visited = HashSet::from([(0, 0)]);
+        for line in input.lines() {
+            let (direction, steps) = line.split_once(' ').unwrap();
+            let (dx, dy) = match direction {
+                "U" => (0, -1),
+                "R" => (1, 0),
+                "D" => (0, 1),
+                _ => (-1, 0),
+            };
+            for _ in 0..steps.parse::<usize>().unwrap() {
+                rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                for i in 1..knots {
+                    let (x, y) = (rope[i - 1].0 - rope[i].0, rope[i - 1].1 - rope[i].1);
+                    if x.abs() > 1 || y.abs() > 1 {
+                        rope[i] = (rope[i].0 + x.signum(), rope[i].1 + y.signum());
+                    }
+                }
+                visited.insert(rope[knots - 1]);
+            }
+        }
+        visited.len()
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..60_usize) {
+            let input = generate::to_string(Day09::DAY, size, seed).unwrap();
+            prop_assert_eq!(Day09::solve_a(&input), Ok(naive_tail_visits(&input, 2).into()));
+            prop_assert_eq!(Day09::solve_b(&input), Ok(naive_tail_visits(&input, 10).into()));
+        }
     }
 }
@@ -1,22 +1,45 @@
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let mut program = Program::new_from_input(input);
-    program.execute();
-    let values = [20, 60, 100, 140, 180, 220]
-        .into_iter()
-        .filter_map(|cycle| program.x_signal_strength(cycle))
-        .collect::<Vec<_>>();
-    values.iter().sum::<isize>().to_string()
-}
+use common::{Answer, Bitmap, Solution, SolveError, SolveResult};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
+
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| Instruction::try_from(line).map_err(|e| SolveError::at_line(i, e)))
+            .collect()
+    }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let mut program = Program::new_from_input(input);
-    program.execute();
-    program.draw(40, 6)
+    fn part_a(instructions: &Self::Input) -> SolveResult<Answer> {
+        let mut program = Program::new(instructions.clone());
+        program.execute()?;
+        let mut sum = 0_isize;
+        for cycle in [20, 60, 100, 140, 180, 220] {
+            if let Some(strength) = program.x_signal_strength(cycle)? {
+                sum = sum
+                    .checked_add(strength)
+                    .ok_or("Sum of signal strengths overflows")?;
+            }
+        }
+        Ok(sum.into())
+    }
+
+    fn part_b(instructions: &Self::Input) -> SolveResult<Answer> {
+        let mut program = Program::new(instructions.clone());
+        program.execute()?;
+        Ok(program.draw(40, 6)?.into())
+    }
 }
 
-enum Instruction {
+#[derive(Clone)]
+pub enum Instruction {
     NoOp,
     AddToX(isize),
 }
@@ -35,15 +58,6 @@ struct Registers {
 }
 
 impl Program {
-    fn new_from_input(input: &str) -> Self {
-        let instructions = input
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(Instruction::try_from)
-            .collect::<Result<Vec<_>, _>>()
-            .expect("failed to parse instructions");
-        Self::new(instructions)
-    }
     fn new(instructions: Vec<Instruction>) -> Self {
         Self {
             instructions,
@@ -54,31 +68,44 @@ impl Program {
         }
     }
 
-    fn execute_cycle(&mut self) -> bool {
+    fn execute_cycle(&mut self) -> Result<bool, String> {
         if let Some(instruction) = self.instructions.get(self.instruction_pointer) {
             self.cycles.push(self.registers.clone());
             self.instruction_cycle_count += 1;
-            if instruction.execute_cycle(self.instruction_cycle_count, &mut self.registers) {
+            let done = instruction
+                .execute_cycle(self.instruction_cycle_count, &mut self.registers)
+                .ok_or_else(|| format!("X overflows during cycle {}", self.cycles.len()))?;
+            if done {
                 self.instruction_pointer += 1;
                 self.instruction_cycle_count = 0;
             }
-            true
+            Ok(true)
         } else {
             self.cycles.push(self.registers.clone());
-            false
+            Ok(false)
         }
     }
 
-    fn execute(&mut self) {
-        while self.execute_cycle() {}
+    fn execute(&mut self) -> Result<(), String> {
+        while self.execute_cycle()? {}
+        Ok(())
     }
 
-    fn x_signal_strength(&self, cycle: usize) -> Option<isize> {
-        self.cycles.get(cycle - 1).map(|p| cycle as isize * p.x)
+    /// `None` if the program ended before `cycle`.
+    fn x_signal_strength(&self, cycle: usize) -> Result<Option<isize>, String> {
+        self.cycles
+            .get(cycle - 1)
+            .map(|p| {
+                isize::try_from(cycle)
+                    .ok()
+                    .and_then(|cycle| cycle.checked_mul(p.x))
+                    .ok_or_else(|| format!("Signal strength of cycle {cycle} overflows"))
+            })
+            .transpose()
     }
 
-    fn draw(&self, width: usize, height: usize) -> String {
-        let mut buffer = String::new();
+    fn draw(&self, width: usize, height: usize) -> Result<Bitmap, String> {
+        let mut pixels = Vec::with_capacity(width * height);
         let mut counter = 0;
         let size = width * height;
         while counter < size {
@@ -86,30 +113,25 @@ impl Program {
             let value = self
                 .cycles
                 .get(counter)
-                .map_or_else(|| panic!("missing value for {counter}"), |p| p.x);
-            if (value.saturating_sub(1)..=value.saturating_add(1)).contains(&x) {
-                buffer.push('#');
-            } else {
-                buffer.push('.');
-            }
-            if counter % width == width - 1 && counter != size - 1 {
-                buffer.push('\n');
-            }
+                .ok_or_else(|| format!("program ended before cycle {}", counter + 1))?
+                .x;
+            pixels.push((value.saturating_sub(1)..=value.saturating_add(1)).contains(&x));
             counter += 1;
         }
-        buffer
+        Ok(Bitmap::new(width, pixels))
     }
 }
 
 impl Instruction {
-    fn execute_cycle(&self, cycle_count: usize, persistence: &mut Registers) -> bool {
+    /// Whether the instruction is done, `None` if `X` overflows.
+    fn execute_cycle(&self, cycle_count: usize, persistence: &mut Registers) -> Option<bool> {
         match (self, cycle_count) {
-            (Self::NoOp, _) => true,
+            (Self::NoOp, _) => Some(true),
             (Self::AddToX(value), 2) => {
-                persistence.x += value;
-                true
+                persistence.x = persistence.x.checked_add(*value)?;
+                Some(true)
             }
-            _ => false,
+            _ => Some(false),
         }
     }
 }
@@ -139,25 +161,72 @@ impl TryFrom<&str> for Instruction {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_a() {
         assert_eq!(
-            calc_a(include_str!("../../../inputs/day_10/test_input.txt")),
-            "13140"
+            Day10::solve_a(include_str!("../../../inputs/day_10/test_input.txt")),
+            Ok(13140.into())
         );
     }
     #[test]
     fn test_b() {
         assert_eq!(
-            calc_b(include_str!("../../../inputs/day_10/test_input.txt")),
-            r#"##..##..##..##..##..##..##..##..##..##..
+            Day10::solve_b(include_str!("../../../inputs/day_10/test_input.txt"))
+                .map(|answer| answer.to_string()),
+            Ok(r#"##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
 #####.....#####.....#####.....#####.....
 ######......######......######......####
 #######.......#######.......#######....."#
+                .to_string())
         );
     }
+
+    #[test]
+    fn test_overflow() {
+        let error = Day10::solve_a("addx 9223372036854775806\naddx 1\n").unwrap_err();
+        assert_eq!(error.reason, "X overflows during cycle 4");
+        let noops = "noop\n".repeat(220);
+        let error = Day10::solve_a(&format!("addx 4611686018427387904\n{noops}")).unwrap_err();
+        assert_eq!(error.reason, "Signal strength of cycle 20 overflows");
+        // Every strength fits, their sum does not.
+        let error = Day10::solve_a(&format!("addx 13000000000000000\n{noops}")).unwrap_err();
+        assert_eq!(error.reason, "Sum of signal strengths overflows");
+    }
+
+    /// Value of `X` during every cycle.
+    fn naive_cycles(input: &str) -> Vec<isize> {
+        let mut x = 1;
+        let mut cycles = Vec::new();
+        for line in input.lines() {
+            cycles.push(x);
+            if let Some(value) = line.strip_prefix("addx ") {
+                cycles.push(x);
+                x += value.parse::<isize>().unwrap();
+            }
+        }
+        cycles
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..400_usize) {
+            let input = generate::to_string(Day10::DAY, size, seed).unwrap();
+            let cycles = naive_cycles(&input);
+            let strength = [20, 60, 100, 140, 180, 220]
+                .into_iter()
+                .map(|cycle| cycle as isize * cycles[cycle - 1])
+                .sum::<isize>();
+            let pixels = (0..240)
+                .map(|cycle| (cycles[cycle] - (cycle % 40) as isize).abs() <= 1)
+                .collect();
+            prop_assert_eq!(Day10::solve_a(&input), Ok(strength.into()));
+            prop_assert_eq!(Day10::solve_b(&input), Ok(Bitmap::new(40, pixels).into()));
+        }
+    }
 }
@@ -1,20 +1,59 @@
 use std::{cmp::Reverse, collections::VecDeque};
 
+use common::{Answer, Solution, SolveError, SolveResult};
+use parse::{
+    blocks, field, list, located,
+    nom::{
+        branch::alt,
+        character::complete::{char, line_ending},
+        combinator::{map, value},
+        sequence::{delimited, preceded, terminated, tuple},
+    },
+    parse_all, tag, unsigned, ParseResult, Span,
+};
+
 static THRESHOLD: u64 = u32::MAX as u64 - 1;
 
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    execute_program(input, 20, Some(3)).to_string()
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+
+    type Input = Vec<Monkey>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        parse_monkeys(input)
+    }
+
+    fn part_a(monkeys: &Self::Input) -> SolveResult<Answer> {
+        Ok(monkey_business(monkeys, 20, Some(3))?.into())
+    }
+
+    fn part_b(monkeys: &Self::Input) -> SolveResult<Answer> {
+        Ok(monkey_business(monkeys, 10000, None)?.into())
+    }
 }
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    execute_program(input, 10000, None).to_string()
+/// Product of the two highest inspection counts after `rounds`, dividing
+/// worry levels by `divide_by` after each inspection, if any.
+///
+/// # Errors
+/// If there are fewer than 2 monkeys or a worry level overflows.
+pub fn monkey_business(
+    monkeys: &[Monkey],
+    rounds: usize,
+    divide_by: Option<u64>,
+) -> SolveResult<usize> {
+    Ok(execute_program(monkeys.to_vec(), rounds, divide_by)?)
 }
 
 #[allow(clippy::iter_with_drain)]
-fn execute_program(input: &str, rounds: usize, divide_by: Option<u64>) -> usize {
-    let mut monkeys = parse_monkeys(input);
+fn execute_program(
+    mut monkeys: Vec<Monkey>,
+    rounds: usize,
+    divide_by: Option<u64>,
+) -> Result<usize, String> {
     let items_len = monkeys.iter().map(|m| m.items.len()).sum::<usize>();
     let mut monkeys_count = monkeys.iter().map(|_| 0).collect::<Vec<_>>();
     let mut outputs = Vec::<(usize, Item)>::with_capacity(items_len);
@@ -26,11 +65,7 @@ fn execute_program(input: &str, rounds: usize, divide_by: Option<u64>) -> usize
             outputs = p.1;
             monkey.items.extend(p.0.into_iter().map(|(_, item)| item));
 
-            let id = monkey.id;
-            let output = monkey.execute_round(divide_by);
-            monkeys_count[id] += output.len();
-
-            outputs.extend(output);
+            monkeys_count[monkey.id] += monkey.execute_round(divide_by, &mut outputs)?;
         }
         outputs.drain(..).for_each(|(dest, value)| {
             monkeys[dest].items.push_back(value);
@@ -38,30 +73,55 @@ fn execute_program(input: &str, rounds: usize, divide_by: Option<u64>) -> usize
     }
 
     monkeys_count.sort_by_key(|c| Reverse(*c));
-    monkeys_count[..2]
-        .iter()
-        .copied()
-        .reduce(|a, b| a * b)
-        .expect("failed to get monkeys count")
+    match monkeys_count[..] {
+        [first, second, ..] => Ok(first * second),
+        _ => Err(format!(
+            "expected at least 2 monkeys, found {}",
+            monkeys.len()
+        )),
+    }
 }
 
-fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    input
-        .split("\n\n")
-        .filter(|l| !l.is_empty())
-        .map(Monkey::try_from)
-        .collect::<Result<_, _>>()
-        .expect("failed to parse monkeys")
+fn parse_monkeys(input: &str) -> SolveResult<Vec<Monkey>> {
+    let monkeys = parse_all(input, blocks(located(monkey)))?;
+
+    for (i, (line, monkey)) in monkeys.iter().enumerate() {
+        if monkey.id != i {
+            return Err(
+                SolveError::new(format!("expected monkey {i}, found {}", monkey.id))
+                    .with_line(*line),
+            );
+        }
+        if monkey.test.divisible_by == 0 {
+            // The test comes three lines below the header of the monkey.
+            return Err(
+                SolveError::new(format!("monkey {} tests divisibility by 0", monkey.id))
+                    .with_line(line + 3),
+            );
+        }
+        for dest in [monkey.test.dest_if_true, monkey.test.dest_if_false] {
+            if dest >= monkeys.len() {
+                return Err(SolveError::new(format!(
+                    "monkey {} throws to missing monkey {dest}",
+                    monkey.id
+                ))
+                .with_line(*line));
+            }
+        }
+    }
+
+    Ok(monkeys.into_iter().map(|(_, monkey)| monkey).collect())
 }
 
-struct Monkey {
+#[derive(Clone)]
+pub struct Monkey {
     id: usize,
     items: VecDeque<Item>,
     operation: Operation,
     test: Test,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Item {
     value: u64,
     operations: Vec<Operation>,
@@ -74,6 +134,7 @@ enum Operation {
     Add(u64),
 }
 
+#[derive(Clone)]
 struct Test {
     divisible_by: u64,
     dest_if_true: usize,
@@ -81,16 +142,22 @@ struct Test {
 }
 
 impl Monkey {
+    /// Throws every item into `outputs`, returning how many were inspected.
     fn execute_round(
         &mut self,
         divide_by: Option<u64>,
-    ) -> impl ExactSizeIterator<Item = (usize, Item)> + '_ {
-        let test = &self.test;
-        let operation = self.operation;
-        self.items.drain(..).map(move |mut item| {
-            item.operations.push(operation);
-            (test.get_dest(&item, divide_by), item)
-        })
+        outputs: &mut Vec<(usize, Item)>,
+    ) -> Result<usize, String> {
+        let inspected = self.items.len();
+        for mut item in self.items.drain(..) {
+            item.operations.push(self.operation);
+            let dest = self
+                .test
+                .get_dest(&item, divide_by)
+                .ok_or_else(|| format!("monkey {}: worry level overflows", self.id))?;
+            outputs.push((dest, item));
+        }
+        Ok(inspected)
     }
 }
 
@@ -102,191 +169,191 @@ impl Item {
         }
     }
 
-    fn get_mod_equivalent(&self, n: u64, divide_by: Option<u64>) -> u64 {
+    /// `None` if the worry level overflows.
+    fn get_mod_equivalent(&self, n: u64, divide_by: Option<u64>) -> Option<u64> {
         divide_by.map_or_else(
             || {
                 self.operations
                     .iter()
-                    .fold(self.value, |acc, op| op.apply_with_mod(acc, n))
+                    .try_fold(self.value, |acc, op| op.apply_with_mod(acc, n))
             },
             |divide_by| {
                 self.operations
                     .iter()
-                    .fold(self.value, |acc, op| op.apply(acc, divide_by))
+                    .try_fold(self.value, |acc, op| op.apply(acc, divide_by))
             },
         )
     }
 
-    fn is_divisible_by(&self, n: u64, divide_by: Option<u64>) -> bool {
-        self.get_mod_equivalent(n, divide_by) % n == 0
+    fn is_divisible_by(&self, n: u64, divide_by: Option<u64>) -> Option<bool> {
+        Some(self.get_mod_equivalent(n, divide_by)?.is_multiple_of(n))
     }
 }
 
 impl Operation {
-    fn apply_with_mod(&self, mut value: u64, n: u64) -> u64 {
+    /// `None` if the result overflows.
+    fn apply_with_mod(&self, mut value: u64, n: u64) -> Option<u64> {
+        if value > THRESHOLD {
+            value %= n;
+        }
         match self {
-            Self::Pow2 => {
-                if value > THRESHOLD {
-                    value %= n;
-                }
-                value * value
-            }
-            Self::Multiply(m) => {
-                if value > THRESHOLD {
-                    value %= n;
-                }
-                value * m
-            }
-            Self::Add(a) => value + a,
+            Self::Pow2 => value.checked_mul(value),
+            Self::Multiply(m) => value.checked_mul(*m),
+            Self::Add(a) => value.checked_add(*a),
         }
     }
 
-    fn apply(&self, value: u64, divide_by: u64) -> u64 {
-        (match self {
-            Self::Pow2 => value * value,
-            Self::Multiply(m) => value * m,
-            Self::Add(a) => value + a,
-        }) / divide_by
+    /// `None` if the result overflows.
+    fn apply(&self, value: u64, divide_by: u64) -> Option<u64> {
+        let value = match self {
+            Self::Pow2 => value.checked_mul(value),
+            Self::Multiply(m) => value.checked_mul(*m),
+            Self::Add(a) => value.checked_add(*a),
+        }?;
+        Some(value / divide_by)
     }
 }
 
 impl Test {
-    fn get_dest(&self, item: &Item, divide_by: Option<u64>) -> usize {
-        if item.is_divisible_by(self.divisible_by, divide_by) {
+    /// `None` if the worry level of `item` overflows.
+    fn get_dest(&self, item: &Item, divide_by: Option<u64>) -> Option<usize> {
+        Some(if item.is_divisible_by(self.divisible_by, divide_by)? {
             self.dest_if_true
         } else {
             self.dest_if_false
-        }
+        })
     }
 }
 
-impl TryFrom<&str> for Monkey {
-    type Error = String;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut lines = value.lines().filter(|l| !l.is_empty());
-        let id = lines
-            .next()
-            .ok_or_else(|| "missing id line".to_string())?
-            .split_whitespace()
-            .next_back()
-            .ok_or_else(|| "missing id value".to_string())?
-            .trim_end_matches(':')
-            .parse()
-            .map_err(|e| format!("failed to parse id: {e}"))?;
-        let items = lines
-            .next()
-            .ok_or_else(|| "missing items line".to_string())?
-            .split_once(": ")
-            .ok_or_else(|| "missing items separator".to_string())?
-            .1
-            .split(", ")
-            .filter_map(|v| Some(Item::new(v.parse().ok()?)))
-            .collect();
-        let operation = lines
-            .next()
-            .ok_or_else(|| "missing operation line".to_string())?
-            .try_into()?;
-        let test = value
-            .split_once("Test:")
-            .ok_or_else(|| "missing test line".to_string())?
-            .1
-            .try_into()?;
-        Ok(Self {
+/// ```text
+/// Monkey 0:
+///   Starting items: 79, 98
+///   Operation: new = old * 19
+///   Test: divisible by 23
+///     If true: throw to monkey 2
+///     If false: throw to monkey 3
+/// ```
+fn monkey(input: Span<'_>) -> ParseResult<'_, Monkey> {
+    map(
+        tuple((
+            terminated(delimited(tag("Monkey "), unsigned, char(':')), line_ending),
+            terminated(field("Starting items", list(", ", unsigned)), line_ending),
+            terminated(field("Operation", operation), line_ending),
+            test,
+        )),
+        |(id, items, operation, test)| Monkey {
             id,
-            items,
+            items: items.into_iter().map(Item::new).collect(),
             operation,
             test,
-        })
-    }
+        },
+    )(input)
 }
 
-impl TryFrom<&str> for Test {
-    type Error = String;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut lines = value.lines().filter(|l| !l.is_empty());
-        let divisible_by = lines
-            .next()
-            .ok_or_else(|| "missing divisible by line".to_string())?
-            .split_whitespace()
-            .next_back()
-            .ok_or_else(|| "missing divisible by value".to_string())?
-            .parse()
-            .map_err(|e| format!("failed to parse divisible by: {e}"))?;
-        let dest_if_true = lines
-            .next()
-            .ok_or_else(|| "missing dest if true line".to_string())?
-            .split_whitespace()
-            .next_back()
-            .ok_or_else(|| "missing dest if true value".to_string())?
-            .parse()
-            .map_err(|e| format!("failed to parse dest if true: {e}"))?;
-        let dest_if_false = lines
-            .next()
-            .ok_or_else(|| "missing dest if false line".to_string())?
-            .split_whitespace()
-            .next_back()
-            .ok_or_else(|| "missing dest if false value".to_string())?
-            .parse()
-            .map_err(|e| format!("failed to parse dest if false: {e}"))?;
-
-        Ok(Self {
+fn operation(input: Span<'_>) -> ParseResult<'_, Operation> {
+    preceded(
+        tag("new = old "),
+        alt((
+            value(Operation::Pow2, tag("* old")),
+            map(preceded(tag("* "), unsigned), Operation::Multiply),
+            map(preceded(tag("+ "), unsigned), Operation::Add),
+        )),
+    )(input)
+}
+
+fn test(input: Span<'_>) -> ParseResult<'_, Test> {
+    map(
+        tuple((
+            terminated(
+                field("Test", preceded(tag("divisible by "), unsigned)),
+                line_ending,
+            ),
+            terminated(
+                field("If true", preceded(tag("throw to monkey "), unsigned)),
+                line_ending,
+            ),
+            field("If false", preceded(tag("throw to monkey "), unsigned)),
+        )),
+        |(divisible_by, dest_if_true, dest_if_false)| Test {
             divisible_by,
             dest_if_true,
             dest_if_false,
-        })
-    }
-}
-
-impl TryFrom<&str> for Operation {
-    type Error = String;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut parts = value.split(' ');
-        let operation_value = parts
-            .next_back()
-            .ok_or_else(|| "missing operation value".to_string())?;
-        let signal = parts
-            .next_back()
-            .ok_or_else(|| "missing operation signal".to_string())?;
-        match (signal, operation_value) {
-            ("*", "old") => Ok(Self::Pow2),
-            ("*", value) => {
-                Ok(Self::Multiply(value.parse().map_err(|e| {
-                    format!("failed to parse operation value: {e}")
-                })?))
-            }
-            ("+", value) => {
-                Ok(Self::Add(value.parse().map_err(|e| {
-                    format!("failed to parse operation value: {e}")
-                })?))
-            }
-            _ => Err(format!("invalid operation: {value}")),
-        }
-    }
+        },
+    )(input)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
-    fn test_calc_a() {
+    fn test_part_a() {
         assert_eq!(
-            calc_a(include_str!("../../../inputs/day_11/test_input.txt")),
-            "10605"
+            Day11::solve_a(include_str!("../../../inputs/day_11/test_input.txt")),
+            Ok(10605.into())
         );
     }
 
     #[test]
-    fn test_calc_b() {
+    fn test_part_b() {
         assert_eq!(
-            calc_b(include_str!("../../../inputs/day_11/test_input.txt")),
-            "2713310158"
+            Day11::solve_b(include_str!("../../../inputs/day_11/test_input.txt")),
+            Ok(2_713_310_158_u64.into())
         );
     }
 
+    #[test]
+    fn test_parse_error() {
+        let input = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old / 19
+  Test: divisible by 23
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let error = Day11::parse(input)
+            .err()
+            .expect("division is not an operation");
+        assert_eq!((error.line, error.column), (Some(3), Some(24)));
+    }
+
+    #[test]
+    fn test_zero_divisor() {
+        let input = "Monkey 0:
+  Starting items: 79
+  Operation: new = old * 19
+  Test: divisible by 0
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let error = Day11::parse(input).err().expect("0 divides nothing");
+        assert_eq!(error.line, Some(4));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "Monkey 0:
+  Starting items: 4294967296
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 1
+  Operation: new = old + 18446744073709551615
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let error = Day11::solve_a(input).unwrap_err();
+        assert_eq!(error.reason, "monkey 0: worry level overflows");
+        let error = Day11::solve_b(input).unwrap_err();
+        assert_eq!(error.reason, "monkey 1: worry level overflows");
+    }
+
     #[test]
     fn test_item_1() {
         let n = 5;
@@ -295,11 +362,11 @@ mod tests {
             operations: vec![Operation::Pow2],
         };
 
-        assert_eq!(item.get_mod_equivalent(n, None) % n, 4);
+        assert_eq!(item.get_mod_equivalent(n, None).unwrap() % n, 4);
         item.operations.push(Operation::Multiply(3));
-        assert_eq!(item.get_mod_equivalent(n, None) % n, 2);
+        assert_eq!(item.get_mod_equivalent(n, None).unwrap() % n, 2);
         item.operations.push(Operation::Add(1));
-        assert_eq!(item.get_mod_equivalent(n, None) % n, 3);
+        assert_eq!(item.get_mod_equivalent(n, None).unwrap() % n, 3);
     }
 
     #[test]
@@ -309,8 +376,8 @@ mod tests {
             value: 65,
             operations: vec![Operation::Add(6), Operation::Multiply(19)],
         };
-        assert_eq!(item.get_mod_equivalent(n, Some(3)) % n, 7);
-        assert!(!item.is_divisible_by(n, Some(3)));
+        assert_eq!(item.get_mod_equivalent(n, Some(3)).unwrap() % n, 7);
+        assert_eq!(item.is_divisible_by(n, Some(3)), Some(false));
     }
 
     #[test]
@@ -324,7 +391,85 @@ mod tests {
                 Operation::Add(6),
             ],
         };
-        assert_eq!(item.get_mod_equivalent(n, Some(3)) % n, 0);
-        assert!(item.is_divisible_by(n, Some(3)));
+        assert_eq!(item.get_mod_equivalent(n, Some(3)).unwrap() % n, 0);
+        assert_eq!(item.is_divisible_by(n, Some(3)), Some(true));
+    }
+
+    /// Plays `rounds` rounds keeping the plain worry levels, reduced by
+    /// `relief` or modulo the product of every divisor.
+    fn naive_business(input: &str, rounds: usize, relief: Option<u64>) -> usize {
+        let monkeys = input
+            .split("\n\n")
+            .map(|block| {
+                let lines = block.lines().collect::<Vec<_>>();
+                let last_number = |line: &str| line.rsplit(' ').next().unwrap().to_string();
+                let (_, items) = lines[1].split_once(": ").unwrap();
+                let items = items
+                    .split(", ")
+                    .map(|i| i.parse::<u64>().unwrap())
+                    .collect::<Vec<_>>();
+                let operation = lines[2].rsplitn(3, ' ').collect::<Vec<_>>();
+                let (operand, operator) = (operation[0].to_string(), operation[1].to_string());
+                let divisor = last_number(lines[3]).parse::<u64>().unwrap();
+                let if_true = last_number(lines[4]).parse::<usize>().unwrap();
+                let if_false = last_number(lines[5]).parse::<usize>().unwrap();
+                (items, operator, operand, divisor, if_true, if_false)
+            })
+            .collect::<Vec<_>>();
+        // The divisors are primes, so their product without repeats is a
+        // multiple of all of them.
+        let modulo = monkeys
+            .iter()
+            .map(|m| m.3)
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .product::<u64>();
+        let mut items = monkeys.iter().map(|m| m.0.clone()).collect::<Vec<_>>();
+        let mut inspections = vec![0; monkeys.len()];
+        for _ in 0..rounds {
+            for (id, (_, operator, operand, divisor, if_true, if_false)) in
+                monkeys.iter().enumerate()
+            {
+                for item in std::mem::take(&mut items[id]) {
+                    inspections[id] += 1;
+                    let operand = operand.parse().unwrap_or(item);
+                    let worry = if operator == "*" {
+                        item * operand
+                    } else {
+                        item + operand
+                    };
+                    let worry = relief.map_or(worry % modulo, |relief| worry / relief);
+                    let dest = if worry.is_multiple_of(*divisor) {
+                        if_true
+                    } else {
+                        if_false
+                    };
+                    items[*dest].push(worry);
+                }
+            }
+        }
+        inspections.sort_unstable_by(|a, b| b.cmp(a));
+        inspections[0] * inspections[1]
+    }
+
+    proptest! {
+        #[test]
+        fn test_part_a_matches_naive(seed: u64, size in 2..20_usize) {
+            let input = generate::to_string(Day11::DAY, size, seed).unwrap();
+            let expected = naive_business(&input, 20, Some(3));
+            prop_assert_eq!(Day11::solve_a(&input), Ok(expected.into()));
+        }
+    }
+
+    proptest! {
+        // Part b replays the history of every item, so keep to a few tiny
+        // inputs.
+        #![proptest_config(ProptestConfig::with_cases(3))]
+        #[test]
+        fn test_part_b_matches_naive(seed: u64) {
+            let input = generate::to_string(Day11::DAY, 2, seed).unwrap();
+            let expected = naive_business(&input, 10_000, None);
+            prop_assert_eq!(Day11::solve_b(&input), Ok(expected.into()));
+        }
     }
 }
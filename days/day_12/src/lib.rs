@@ -1,31 +1,38 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let height_map = parse_height_map(input).expect("failed to parse input");
-    height_map.shortest_path(Square::Source).map_or_else(
-        || "No path found".to_string(),
-        |path| (path.len() - 1).to_string(),
-    )
-}
+use common::{Answer, Solution, SolveResult};
+use grid::{Grid, Point};
+use search::{bfs, Cost, Graph};
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let height_map = parse_height_map(input).expect("failed to parse input");
-    height_map.shortest_path(Square::Other(1)).map_or_else(
-        || "No path found".to_string(),
-        |path| (path.len() - 1).to_string(),
-    )
-}
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Hill Climbing Algorithm";
+
+    type Input = HeightMap;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        parse_height_map(input)
+    }
 
-struct HeightMap {
-    squares: Vec<Vec<Square>>,
-    destiny: Cord,
-    edges: HashMap<Cord, Vec<Cord>>,
+    fn part_a(height_map: &Self::Input) -> SolveResult<Answer> {
+        height_map
+            .shortest_path(|square| square == Square::Source)
+            .map(Answer::from)
+            .ok_or_else(|| "No path found".into())
+    }
+
+    fn part_b(height_map: &Self::Input) -> SolveResult<Answer> {
+        height_map
+            .shortest_path(|square| square.elevation() == 1)
+            .map(Answer::from)
+            .ok_or_else(|| "No path found".into())
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Cord(usize, usize);
+pub struct HeightMap {
+    squares: Grid<Square>,
+    destiny: Point,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Square {
@@ -35,120 +42,56 @@ enum Square {
 }
 
 impl HeightMap {
-    fn new(squares: Vec<Vec<Square>>) -> Result<Self, String> {
-        let mut destiny = None;
-        let mut edges = HashMap::new();
-
-        for (y, row) in squares.iter().enumerate() {
-            for (x, square) in row.iter().enumerate() {
-                if matches!(square, Square::Destiny) {
-                    if destiny.is_some() {
-                        return Err("Multiple destinies".to_string());
-                    }
-                    destiny = Some(Cord(x, y));
-                }
-                let cord = Cord(x, y);
-                edges.insert(cord, cord.neighbors(row.len(), squares.len()));
-            }
-        }
-
-        for (c1, v) in &mut edges {
-            let s1 = &squares[c1.1][c1.0];
-            v.retain(|c2| {
-                let s2 = &squares[c2.1][c2.0];
-                match (s1, s2) {
-                    (Square::Other(h1), Square::Other(h2)) => *h1 <= h2 + 1,
-                    (Square::Other(h), Square::Source) => *h == 1,
-                    (Square::Destiny, Square::Other(h)) => *h == 26,
-                    _ => false,
-                }
-            });
+    fn new(squares: Grid<Square>) -> Result<Self, String> {
+        let destinies = squares
+            .iter()
+            .filter(|(_, square)| matches!(square, Square::Destiny))
+            .map(|(p, _)| p)
+            .take(2)
+            .collect::<Vec<_>>();
+        match destinies[..] {
+            [destiny] => Ok(Self { squares, destiny }),
+            [] => Err("No destiny".to_string()),
+            _ => Err("Multiple destinies".to_string()),
         }
-
-        Ok(Self {
-            squares,
-            destiny: destiny.ok_or_else(|| "No destiny".to_string())?,
-            edges,
-        })
     }
 
-    fn shortest_path(&self, goal: Square) -> Option<Vec<Cord>> {
-        let mut distances = HashMap::new();
-        let mut previous = HashMap::new();
-        let mut queue = VecDeque::with_capacity(self.edges.len());
-
-        let mut visisted = HashSet::new();
-        visisted.insert(self.destiny);
-        distances.insert(self.destiny, 0);
-        queue.push_back(self.destiny);
-        let mut found = None;
-        'outer: while let Some(c1) = queue.pop_front() {
-            let d1 = distances[&c1];
-            if let Some(edges) = self.edges.get(&c1) {
-                for c2 in edges {
-                    if visisted.contains(c2) {
-                        continue;
-                    }
-                    visisted.insert(*c2);
-                    distances.insert(*c2, d1 + 1);
-                    previous.insert(*c2, c1);
-                    queue.push_back(*c2);
-                    if self.squares[c2.1][c2.0] == goal {
-                        found = Some(*c2);
-                        break 'outer;
-                    }
-                }
-            }
-        }
+    /// Steps from the destiny down to the nearest square matching `is_goal`.
+    fn shortest_path(&self, is_goal: impl Fn(Square) -> bool) -> Option<Cost> {
+        bfs(self, [self.destiny], |&p| is_goal(self.squares[p]))
+            .path
+            .map(|path| path.cost)
+    }
+}
 
-        let mut path = Vec::new();
-        let mut c = found?;
-        while c != self.destiny {
-            path.push(c);
-            if let Some(&p) = previous.get(&c) {
-                c = p;
-            } else {
-                return None;
-            }
-        }
-        path.push(self.destiny);
-        Some(path)
+/// The climb walked backwards, from the destiny down: a step may go down
+/// any number of levels but up at most one.
+impl Graph for HeightMap {
+    type Node = Point;
+
+    fn successors(&self, &p: &Point) -> impl Iterator<Item = (Point, Cost)> {
+        let elevation = self.squares[p].elevation();
+        self.squares
+            .neighbours4(p)
+            .filter(move |&n| elevation <= self.squares[n].elevation() + 1)
+            .map(|n| (n, 1))
     }
 }
 
-impl Cord {
-    fn neighbors(self, max_x: usize, max_y: usize) -> Vec<Self> {
-        let Self(x, y) = self;
-        let mut neighbors = Vec::with_capacity(4);
-        if x > 0 {
-            neighbors.push(Self(x - 1, y));
-        }
-        if y > 0 {
-            neighbors.push(Self(x, y - 1));
+impl Square {
+    /// The source stands at elevation `a` and the destiny at `z`.
+    fn elevation(self) -> u8 {
+        match self {
+            Self::Source => 1,
+            Self::Destiny => 26,
+            Self::Other(h) => h,
         }
-        if x < max_x - 1 {
-            neighbors.push(Self(x + 1, y));
-        }
-        if y < max_y - 1 {
-            neighbors.push(Self(x, y + 1));
-        }
-        neighbors
     }
 }
 
-fn parse_height_map(input: &str) -> Result<HeightMap, String> {
-    HeightMap::new(
-        input
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(|r| {
-                r.chars()
-                    .map(TryInto::try_into)
-                    .collect::<Result<Vec<_>, _>>()
-                    .expect("Invalid row")
-            })
-            .collect(),
-    )
+fn parse_height_map(input: &str) -> SolveResult<HeightMap> {
+    let squares = Grid::parse(input, Square::try_from)?;
+    Ok(HeightMap::new(squares)?)
 }
 
 impl TryFrom<char> for Square {
@@ -165,28 +108,91 @@ impl TryFrom<char> for Square {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use common::Solution;
+    use proptest::prelude::*;
+
+    use super::Day12;
 
     #[test]
-    fn test_calc_a_1() {
+    fn test_part_a_1() {
         let input = include_str!("../../../inputs/day_12/test_input.txt");
-        assert_eq!(super::calc_a(input), "31");
+        assert_eq!(Day12::solve_a(input), Ok(31.into()));
     }
 
     #[test]
-    fn test_calc_a_2() {
+    fn test_part_a_2() {
         let input = include_str!("../../../inputs/day_12/input.txt");
-        assert_eq!(super::calc_a(input), "449");
+        assert_eq!(Day12::solve_a(input), Ok(449.into()));
     }
 
     #[test]
-    fn test_calc_b_1() {
+    fn test_part_b_1() {
         let input = include_str!("../../../inputs/day_12/test_input.txt");
-        assert_eq!(super::calc_b(input), "29");
+        assert_eq!(Day12::solve_b(input), Ok(29.into()));
     }
 
     #[test]
-    fn test_calc_b_2() {
+    fn test_part_b_2() {
         let input = include_str!("../../../inputs/day_12/input.txt");
-        assert_eq!(super::calc_b(input), "443");
+        assert_eq!(Day12::solve_b(input), Ok(443.into()));
+    }
+
+    #[test]
+    fn test_source_and_destiny_elevations() {
+        assert_eq!(Day12::solve_a("SbcdefghijklmnopqrstuvwxyzE"), Ok(26.into()));
+        assert_eq!(Day12::solve_a("SabcdefghijklmnopqrstuvwxyE"), Ok(26.into()));
+    }
+
+    /// Breadth first search climbing from every square of `starts` to `E`.
+    fn naive_steps(input: &str, starts: &[u8]) -> Option<usize> {
+        let rows = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let elevation = |c: u8| match c {
+            b'S' => b'a',
+            b'E' => b'z',
+            c => c,
+        };
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if starts.contains(c) {
+                    seen.insert((x, y));
+                    queue.push_back((x, y, 0));
+                }
+            }
+        }
+        while let Some((x, y, steps)) = queue.pop_front() {
+            if rows[y][x] == b'E' {
+                return Some(steps);
+            }
+            let neighbours = [
+                (x + 1, y),
+                (x, y + 1),
+                (x.wrapping_sub(1), y),
+                (x, y.wrapping_sub(1)),
+            ];
+            for (nx, ny) in neighbours {
+                let Some(&next) = rows.get(ny).and_then(|row| row.get(nx)) else {
+                    continue;
+                };
+                if elevation(next) <= elevation(rows[y][x]) + 1 && seen.insert((nx, ny)) {
+                    queue.push_back((nx, ny, steps + 1));
+                }
+            }
+        }
+        None
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..1600_usize) {
+            let input = generate::to_string(Day12::DAY, size, seed).unwrap();
+            let a = naive_steps(&input, b"S").unwrap();
+            let b = naive_steps(&input, b"Sa").unwrap();
+            prop_assert_eq!(Day12::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day12::solve_b(&input), Ok(b.into()));
+        }
     }
 }
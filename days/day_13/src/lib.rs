@@ -1,68 +1,78 @@
 #![allow(clippy::unwrap_used, clippy::significant_drop_tightening)]
 
-use std::{cmp::Ordering, str::FromStr};
+use std::{cmp::Ordering, fmt};
 
-use nom::{
-    branch::alt, bytes::complete::take_while1, character::complete::char, combinator::map,
-    multi::separated_list0, sequence::delimited, IResult,
+use common::{Answer, Solution, SolveResult};
+use parse::{
+    blocks,
+    nom::{
+        branch::alt,
+        character::complete::{char, line_ending},
+        combinator::map,
+        error::{ErrorKind, FromExternalError},
+        multi::separated_list0,
+        sequence::{separated_pair, terminated},
+        Err,
+    },
+    parse_all, unsigned, ParseError, ParseResult, Span,
 };
-use nom_locate::LocatedSpan;
-use nom_supreme::error::ErrorTree;
-pub type RawSpan<'a> = LocatedSpan<&'a str>;
-pub type RawParseError<'a> = ErrorTree<RawSpan<'a>>;
-
-pub type ParseResult<'a, T> = IResult<RawSpan<'a>, T, RawParseError<'a>>;
-
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let pairs = parse_input(input);
-    pairs
-        .into_iter()
-        .enumerate()
-        .map(|(i, (a, b))| {
-            if a.is_right_order(&b).expect("invalid packet") {
-                i + 1
-            } else {
-                0
+
+/// Deepest list nesting accepted in a packet.
+const MAX_DEPTH: usize = 100;
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Distress Signal";
+
+    type Input = Vec<(Packet, Packet)>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_a(pairs: &Self::Input) -> SolveResult<Answer> {
+        let mut sum = 0;
+        for (i, (a, b)) in pairs.iter().enumerate() {
+            match a.is_right_order(b) {
+                Some(true) => sum += i + 1,
+                Some(false) => {}
+                None => return Err(format!("packets of pair {} are equal", i + 1).into()),
             }
-        })
-        .sum::<usize>()
-        .to_string()
-}
+        }
+        Ok(sum.into())
+    }
+
+    fn part_b(pairs: &Self::Input) -> SolveResult<Answer> {
+        let first_package = Packet::List(vec![Packet::List(vec![Packet::Number(2)])]);
+        let second_package = Packet::List(vec![Packet::List(vec![Packet::Number(6)])]);
+        let mut packets = pairs
+            .iter()
+            .flat_map(|(a, b)| [a, b])
+            .chain([&first_package, &second_package])
+            .collect::<Vec<_>>();
+        packets.sort_by(|a, b| match a.is_right_order(b) {
+            Some(true) => Ordering::Less,
+            Some(false) => Ordering::Greater,
+            None => Ordering::Equal,
+        });
+
+        let first_pos = packets
+            .iter()
+            .position(|v| **v == first_package)
+            .ok_or("first divider packet not found")?;
+        let second_pos = packets
+            .iter()
+            .position(|v| **v == second_package)
+            .ok_or("second divider packet not found")?;
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let mut input = input.replace("\n\n", "\n");
-    input.push_str(
-        r#"[[2]]
-[[6]]"#,
-    );
-    let mut packets = input
-        .split('\n')
-        .map(|s| parse_element(RawSpan::new(s)).expect("invalid packet").1)
-        .collect::<Vec<_>>();
-    packets.sort_by(|a, b| match a.is_right_order(b) {
-        Some(true) => Ordering::Less,
-        Some(false) => Ordering::Greater,
-        None => Ordering::Equal,
-    });
-
-    let first_package = Packet::List(vec![Packet::List(vec![Packet::Number(2)])]);
-    let second_package = Packet::List(vec![Packet::List(vec![Packet::Number(6)])]);
-    let first_pos = packets
-        .iter()
-        .position(|v| *v == first_package)
-        .expect("first divider packet not found");
-    let second_pos = packets
-        .iter()
-        .position(|v| *v == second_package)
-        .expect("second divider packet not found");
-
-    ((first_pos + 1) * (second_pos + 1)).to_string()
+        Ok(((first_pos + 1) * (second_pos + 1)).into())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Packet {
+pub enum Packet {
     Number(u32),
     List(Vec<Packet>),
 }
@@ -101,60 +111,165 @@ impl Packet {
     }
 }
 
-fn parse_input(input: &str) -> Vec<(Packet, Packet)> {
-    input
-        .split("\n\n")
-        .map(|s| {
-            let lines = s.split_once('\n').unwrap();
-            let a = parse_element(RawSpan::new(lines.0))
-                .expect("invalid packet")
-                .1;
-            let b = parse_element(RawSpan::new(lines.1))
-                .expect("invalid packet")
-                .1;
-            (a, b)
-        })
-        .collect()
+fn parse_input(input: &str) -> SolveResult<Vec<(Packet, Packet)>> {
+    parse_all(
+        input,
+        blocks(separated_pair(parse_element, line_ending, parse_element)),
+    )
+}
+
+fn parse_element(input: Span<'_>) -> ParseResult<'_, Packet> {
+    parse_nested(input, 0)
 }
 
-fn parse_element(input: RawSpan<'_>) -> ParseResult<'_, Packet> {
+fn parse_nested(input: Span<'_>, depth: usize) -> ParseResult<'_, Packet> {
     alt((
-        map(parse_number, Packet::Number),
-        map(parse_list, Packet::List),
+        map(unsigned, Packet::Number),
+        map(|input| parse_list(input, depth), Packet::List),
     ))(input)
 }
-fn parse_number(input: RawSpan<'_>) -> ParseResult<'_, u32> {
-    take_while1(|c: char| c.is_ascii_digit())(input).map(|(i, o)| {
-        let o = u32::from_str(o.fragment()).unwrap();
-        (i, o)
-    })
-}
 
-fn parse_list(input: RawSpan<'_>) -> ParseResult<'_, Vec<Packet>> {
-    delimited(
-        char('['),
-        separated_list0(char(','), parse_element),
+fn parse_list(input: Span<'_>, depth: usize) -> ParseResult<'_, Vec<Packet>> {
+    let (rest, _) = char('[')(input)?;
+    if depth == MAX_DEPTH {
+        let error = ParseError::from_external_error(input, ErrorKind::TooLarge, TooDeep);
+        return Err(Err::Failure(error));
+    }
+    terminated(
+        separated_list0(char(','), |input| parse_nested(input, depth + 1)),
         char(']'),
-    )(input)
+    )(rest)
+}
+
+/// Packets nested deeper would overflow the stack while parsing.
+#[derive(Debug)]
+struct TooDeep;
+
+impl fmt::Display for TooDeep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "packet nested deeper than {MAX_DEPTH} lists")
+    }
 }
 
+impl std::error::Error for TooDeep {}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_a() {
         let input = include_str!("../../../inputs/day_13/test_input.txt");
-        let expected = "13";
-        let actual = calc_a(input);
+        let expected = Ok(13.into());
+        let actual = Day13::solve_a(input);
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn test_b() {
         let input = include_str!("../../../inputs/day_13/test_input.txt");
-        let expected = "140";
-        let actual = calc_b(input);
+        let expected = Ok(140.into());
+        let actual = Day13::solve_b(input);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Day13::parse(
+            "[1,[2]]
+[1,2
+",
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(5)));
+        let error = Day13::parse(
+            "[1]
+[2]
+
+[3]
+",
+        )
+        .unwrap_err();
+        assert_eq!(error.line, Some(5));
+    }
+
+    #[test]
+    fn test_parse_too_deep() {
+        let input = format!("{0}\n{0}\n", "[".repeat(100_000));
+        let error = Day13::parse(&input).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(101)));
+    }
+
+    #[derive(Clone, PartialEq, Eq)]
+    enum Value {
+        Int(u32),
+        List(Vec<Value>),
+    }
+
+    impl Ord for Value {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match (self, other) {
+                (Self::Int(a), Self::Int(b)) => a.cmp(b),
+                (Self::List(a), Self::List(b)) => a.cmp(b),
+                (Self::Int(a), Self::List(b)) => [Self::Int(*a)][..].cmp(b),
+                (Self::List(a), Self::Int(b)) => a[..].cmp(&[Self::Int(*b)]),
+            }
+        }
+    }
+
+    impl PartialOrd for Value {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    /// Recursive descent over the bytes of a packet, leaving the rest.
+    fn naive_parse(s: &[u8]) -> (Value, &[u8]) {
+        if s[0] != b'[' {
+            let digits = s.iter().take_while(|c| c.is_ascii_digit()).count();
+            let number = std::str::from_utf8(&s[..digits]).unwrap();
+            return (Value::Int(number.parse().unwrap()), &s[digits..]);
+        }
+        let mut items = Vec::new();
+        let mut rest = &s[1..];
+        while rest[0] != b']' {
+            let (item, after) = naive_parse(rest);
+            items.push(item);
+            rest = after.strip_prefix(b",").unwrap_or(after);
+        }
+        (Value::List(items), &rest[1..])
+    }
+
+    /// Index sum of ordered pairs, and divider positions counted rather than
+    /// sorted.
+    fn naive(input: &str) -> (usize, usize) {
+        let packets = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| naive_parse(line.as_bytes()).0)
+            .collect::<Vec<_>>();
+        let a = packets
+            .chunks(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] < pair[1])
+            .map(|(i, _)| i + 1)
+            .sum();
+        let divider = |n| Value::List(vec![Value::List(vec![Value::Int(n)])]);
+        let (first, second) = (divider(2), divider(6));
+        let before = |d: &Value| packets.iter().filter(|p| *p < d).count();
+        let b = (before(&first) + 1) * (before(&second) + 2);
+        (a, b)
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..50_usize) {
+            let input = generate::to_string(Day13::DAY, size, seed).unwrap();
+            let (a, b) = naive(&input);
+            prop_assert_eq!(Day13::solve_a(&input), Ok(a.into()));
+            prop_assert_eq!(Day13::solve_b(&input), Ok(b.into()));
+        }
+    }
 }
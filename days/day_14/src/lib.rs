@@ -1,201 +1,180 @@
 #![allow(dead_code, clippy::unwrap_used)]
 
-use std::{fmt::Display, ops::RangeInclusive};
+use std::fmt::Display;
 
-#[must_use]
-pub fn calc_a(input: &str) -> String {
-    let mut grid = Grid::new(input, (500, 0));
-    grid.run().to_string()
-}
+use common::{Answer, Solution, SolveError, SolveResult};
+use grid::{Grid, Point};
+
+/// Rocks further away would make the cave too large to allocate.
+const MAX_COORDINATE: usize = 2_000;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Regolith Reservoir";
 
-#[must_use]
-pub fn calc_b(input: &str) -> String {
-    let mut grid = Grid::new(input, (500, 0));
-    grid.set_floor(grid.size().1 + 1);
-    grid.run().to_string()
+    type Input = Grid<Tile>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        parse_rock_paths(input.lines())
+    }
+
+    fn part_a(tiles: &Self::Input) -> SolveResult<Answer> {
+        let mut cave = Cave::with_tiles(tiles.clone(), Point::new(500, 0));
+        Ok(cave.run()?.into())
+    }
+
+    fn part_b(tiles: &Self::Input) -> SolveResult<Answer> {
+        let mut cave = Cave::with_tiles(tiles.clone(), Point::new(500, 0));
+        cave.set_floor(cave.tiles.height() + 1);
+        Ok(cave.run()?.into())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Tile {
+pub enum Tile {
     Air,
     Sand,
     Rock,
 }
 
-struct Grid {
-    matrix: Matrix,
-    sand_source: (usize, usize),
+struct Cave {
+    tiles: Grid<Tile>,
+    sand_source: Point,
     floor: Option<usize>,
 }
 
-struct Matrix {
-    data: Vec<Vec<Tile>>,
-    x_size: usize,
-    y_size: usize,
-}
+impl Cave {
+    fn new(input: &str, sand_source: Point) -> SolveResult<Self> {
+        Ok(Self::with_tiles(
+            parse_rock_paths(input.lines())?,
+            sand_source,
+        ))
+    }
 
-impl Grid {
-    fn new(input: &str, sand_source: (usize, usize)) -> Self {
+    const fn with_tiles(tiles: Grid<Tile>, sand_source: Point) -> Self {
         Self {
-            matrix: Matrix::new_from_rock_paths(input.lines()),
+            tiles,
             sand_source,
             floor: None,
         }
     }
 
     fn set_floor(&mut self, y: usize) {
-        self.matrix.grow_y(y + 1);
+        self.tiles.grow(0, y + 1, Tile::Air);
         self.floor = Some(y);
     }
 
-    const fn size(&self) -> (usize, usize) {
-        self.matrix.size()
-    }
-
-    fn run(&mut self) -> usize {
+    fn run(&mut self) -> Result<usize, String> {
         for i in 0.. {
-            if self.drop_sand() {
-                return i;
+            if self.drop_sand()? {
+                return Ok(i);
             }
         }
 
         unreachable!()
     }
 
-    fn drop_sand(&mut self) -> bool {
-        let (mut x, mut y) = self.sand_source;
-        if *self.matrix.get(x, y) == Tile::Sand {
-            return true;
+    /// Tiles out of the grid are air.
+    fn get(&self, x: usize, y: usize) -> Tile {
+        self.tiles
+            .get(Point::new(x, y))
+            .copied()
+            .unwrap_or(Tile::Air)
+    }
+
+    fn fill_tile(&mut self, tile: Tile, x: usize, y: usize) {
+        self.tiles.grow(x + 1, y + 1, Tile::Air);
+        self.tiles[Point::new(x, y)] = tile;
+    }
+
+    fn drop_sand(&mut self) -> Result<bool, String> {
+        let Point { mut x, mut y } = self.sand_source;
+        if self.get(x, y) == Tile::Sand {
+            return Ok(true);
         }
         loop {
             if self.floor == Some(y + 1) {
-                self.matrix.fill_tile(Tile::Sand, x, y);
-                return false;
+                self.fill_tile(Tile::Sand, x, y);
+                return Ok(false);
             }
-            if self.matrix.row_is_void(y + 1) {
-                return true;
+            if y + 1 >= self.tiles.height() {
+                return Ok(true);
             }
 
-            if *self.matrix.get(x, y + 1) == Tile::Air {
+            if self.get(x, y + 1).is_empty() {
                 y += 1;
-            } else if *self.matrix.get(x - 1, y + 1) == Tile::Air {
+            } else if self
+                .get(x.checked_sub(1).ok_or("sand fell past x = 0")?, y + 1)
+                .is_empty()
+            {
                 x -= 1;
                 y += 1;
-            } else if *self.matrix.get(x + 1, y + 1) == Tile::Air {
+            } else if self.get(x + 1, y + 1).is_empty() {
                 x += 1;
                 y += 1;
             } else {
-                self.matrix.fill_tile(Tile::Sand, x, y);
-                return false;
+                self.fill_tile(Tile::Sand, x, y);
+                return Ok(false);
             }
         }
     }
 }
 
-impl Matrix {
-    const fn new() -> Self {
-        Self {
-            data: Vec::new(),
-            x_size: 0,
-            y_size: 0,
-        }
-    }
-
-    const fn size(&self) -> (usize, usize) {
-        (self.x_size, self.y_size)
+fn parse_rock_paths<'a>(rock_paths: impl Iterator<Item = &'a str>) -> SolveResult<Grid<Tile>> {
+    let mut tiles = Grid::new(0, 0, Tile::Air);
+    for (i, s) in rock_paths.enumerate() {
+        add_rock_path(&mut tiles, s).map_err(|e| SolveError::at_line(i, e))?;
     }
+    Ok(tiles)
+}
 
-    fn new_from_rock_paths<'a>(rock_paths: impl Iterator<Item = &'a str>) -> Self {
-        let mut matrix = Self::new();
-        rock_paths.for_each(|s| matrix.add_rock_path(s));
-        matrix
+fn add_rock_path(tiles: &mut Grid<Tile>, rock_path: &str) -> Result<(), String> {
+    if rock_path.is_empty() {
+        return Ok(());
     }
-
-    fn add_rock_path(&mut self, rock_path: &str) {
-        let paths = rock_path
-            .split(" -> ")
-            .filter_map(|path| {
-                let (s1, s2) = path.split_once(',')?;
-                Some((s1.parse::<usize>().ok()?, s2.parse::<usize>().ok()?))
-            })
-            .collect::<Vec<_>>();
-        for ((mut x1, mut y1), (mut x2, mut y2)) in paths.windows(2).map(|w| (w[0], w[1])) {
-            if x1 > x2 {
-                std::mem::swap(&mut x1, &mut x2);
-            }
-            if y1 > y2 {
-                std::mem::swap(&mut y1, &mut y2);
+    let paths = rock_path
+        .split(" -> ")
+        .map(|path| {
+            let invalid_point = || format!("invalid point: {path}");
+            let (s1, s2) = path.split_once(',').ok_or_else(invalid_point)?;
+            let x = s1.parse::<usize>().map_err(|_| invalid_point())?;
+            let y = s2.parse::<usize>().map_err(|_| invalid_point())?;
+            if x > MAX_COORDINATE || y > MAX_COORDINATE {
+                return Err(format!("point too far from the source: {path}"));
             }
-            self.fill(Tile::Rock, x1..=x2, y1..=y2);
+            Ok((x, y))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    for ((mut x1, mut y1), (mut x2, mut y2)) in paths.windows(2).map(|w| (w[0], w[1])) {
+        if x1 != x2 && y1 != y2 {
+            return Err(format!("diagonal segment {x1},{y1} -> {x2},{y2}"));
         }
-    }
-
-    fn grow_x(&mut self, x_size: usize) {
-        if x_size > self.x_size {
-            self.data
-                .iter_mut()
-                .for_each(|v| v.resize(x_size, Tile::Air));
-            self.x_size = x_size;
+        if x1 > x2 {
+            std::mem::swap(&mut x1, &mut x2);
         }
-    }
-
-    fn grow_y(&mut self, y_size: usize) {
-        if y_size > self.y_size {
-            self.data.resize_with(y_size, || {
-                let mut v = Vec::with_capacity(self.x_size);
-                v.resize(self.x_size, Tile::Air);
-                v
-            });
-            self.y_size = y_size;
+        if y1 > y2 {
+            std::mem::swap(&mut y1, &mut y2);
         }
-    }
-
-    fn grow(&mut self, x_size: usize, y_size: usize) {
-        self.grow_x(x_size);
-        self.grow_y(y_size);
-    }
-
-    fn get(&mut self, x: usize, y: usize) -> &Tile {
-        self.grow(x + 1, y + 1);
-        &self.data[y][x]
-    }
-
-    fn row_is_void(&self, y: usize) -> bool {
-        self.data.get(y).is_none()
-    }
-
-    fn fill_tile(&mut self, tile: Tile, x: usize, y: usize) {
-        self.grow(x + 1, y + 1);
-        self.data[y][x] = tile;
-    }
-
-    fn fill(&mut self, tile: Tile, x_range: RangeInclusive<usize>, y_range: RangeInclusive<usize>) {
-        for y in y_range {
-            self.grow(*x_range.end() + 1, y + 1);
-            for x in x_range.clone() {
-                self.data[y][x] = tile;
+        tiles.grow(x2 + 1, y2 + 1, Tile::Air);
+        for y in y1..=y2 {
+            for x in x1..=x2 {
+                tiles[Point::new(x, y)] = Tile::Rock;
             }
         }
     }
+    Ok(())
 }
 
-impl Display for Matrix {
+impl Display for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.data {
-            for tile in row {
-                write!(
-                    f,
-                    "{}",
-                    match tile {
-                        Tile::Air => '.',
-                        Tile::Sand => 'o',
-                        Tile::Rock => '#',
-                    }
-                )?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        let c = match self {
+            Self::Air => '.',
+            Self::Sand => 'o',
+            Self::Rock => '#',
+        };
+        write!(f, "{c}")
     }
 }
 
@@ -211,30 +190,97 @@ impl Tile {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
     use super::*;
 
     const TEST_INPUT: &str = include_str!("../../../inputs/day_14/test_input.txt");
 
     #[test]
     fn test_a() {
-        let mut grid = Grid::new(TEST_INPUT, (500, 0));
-        assert_eq!(grid.run(), 24);
+        let mut cave = Cave::new(TEST_INPUT, Point::new(500, 0)).unwrap();
+        assert_eq!(cave.run(), Ok(24));
     }
 
     #[test]
     fn test_b() {
-        let mut grid = Grid::new(TEST_INPUT, (500, 0));
-        std::fs::write("test.txt", grid.matrix.to_string()).expect("failed to write file");
-        grid.set_floor(grid.size().1 + 1);
-        assert_eq!(grid.run(), 93);
+        let mut cave = Cave::new(TEST_INPUT, Point::new(500, 0)).unwrap();
+        std::fs::write("test.txt", cave.tiles.to_string()).expect("failed to write file");
+        cave.set_floor(cave.tiles.height() + 1);
+        assert_eq!(cave.run(), Ok(93));
+    }
+
+    #[test]
+    fn test_point_too_far() {
+        let error = Day14::parse("498,4 -> 498,6\n503,4 -> 5555555498,4\n").unwrap_err();
+        assert_eq!(error.line, Some(2));
     }
 
     #[test]
     fn test_input() {
-        let mut grid = Grid::new(include_str!("../../../inputs/day_14/input.txt"), (500, 0));
-        grid.set_floor(grid.size().1 + 1);
-        assert_eq!(grid.run(), 27324);
+        let input = include_str!("../../../inputs/day_14/input.txt");
+        let mut cave = Cave::new(input, Point::new(500, 0)).unwrap();
+        cave.set_floor(cave.tiles.height() + 1);
+        assert_eq!(cave.run(), Ok(27324));
+
+        std::fs::write("test.txt", cave.tiles.to_string()).expect("failed to write file");
+    }
 
-        std::fs::write("test.txt", grid.matrix.to_string()).expect("failed to write file");
+    /// Drops one grain at a time through a set of blocked points, until a
+    /// grain falls below the lowest rock or, with a `floor`, the source is
+    /// blocked.
+    fn naive_sand(input: &str, floor: bool) -> usize {
+        let mut blocked = HashSet::new();
+        for line in input.lines() {
+            let points = line
+                .split(" -> ")
+                .map(|p| {
+                    let (x, y) = p.split_once(',').unwrap();
+                    (x.parse::<i64>().unwrap(), y.parse::<i64>().unwrap())
+                })
+                .collect::<Vec<_>>();
+            for w in points.windows(2) {
+                let ((x1, y1), (x2, y2)) = (w[0], w[1]);
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        blocked.insert((x, y));
+                    }
+                }
+            }
+        }
+        let lowest = blocked.iter().map(|&(_, y)| y).max().unwrap_or(0);
+        let mut grains = 0;
+        while !blocked.contains(&(500, 0)) {
+            let (mut x, mut y) = (500, 0);
+            loop {
+                if floor && y == lowest + 1 {
+                    break;
+                }
+                if !floor && y > lowest {
+                    return grains;
+                }
+                match [x, x - 1, x + 1]
+                    .into_iter()
+                    .find(|&nx| !blocked.contains(&(nx, y + 1)))
+                {
+                    Some(nx) => (x, y) = (nx, y + 1),
+                    None => break,
+                }
+            }
+            blocked.insert((x, y));
+            grains += 1;
+        }
+        grains
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(seed: u64, size in 0..30_usize) {
+            let input = generate::to_string(Day14::DAY, size, seed).unwrap();
+            prop_assert_eq!(Day14::solve_a(&input), Ok(naive_sand(&input, false).into()));
+            prop_assert_eq!(Day14::solve_b(&input), Ok(naive_sand(&input, true).into()));
+        }
     }
 }
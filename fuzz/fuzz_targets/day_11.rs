@@ -0,0 +1,19 @@
+#![no_main]
+
+use common::Solution;
+use day_11::{monkey_business, Day11};
+use libfuzzer_sys::fuzz_target;
+
+// Malformed input must come back as an error, never as a panic, and so must
+// solving any input the parser accepts.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(monkeys) = Day11::parse(input) {
+            let _ = Day11::part_a(&monkeys);
+            // Part b plays 10000 rounds, each replaying the whole history of
+            // every item, far too slow for fuzzing, so fewer rounds of the
+            // same game stand in for it.
+            let _ = monkey_business(&monkeys, 100, None);
+        }
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use common::Solution;
+use day_12::Day12;
+use libfuzzer_sys::fuzz_target;
+
+// Malformed input must come back as an error, never as a panic, and so must
+// solving any input the parser accepts.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(parsed) = Day12::parse(input) {
+            let _ = Day12::part_a(&parsed);
+            let _ = Day12::part_b(&parsed);
+        }
+    }
+});
@@ -0,0 +1,174 @@
+use std::fmt::Display;
+
+/// The solution of one part of a puzzle.
+///
+/// Formats exactly like the plain strings the solvers used to return, so
+/// `answer.to_string()` can be compared against stored answers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// Wide enough to hold any `u64` or `i64` answer without loss.
+    Integer(i128),
+    Text(String),
+    Bitmap(Bitmap),
+}
+
+/// A monochrome image, like the CRT output of day 10.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    width: usize,
+    pixels: Vec<bool>,
+}
+
+impl Answer {
+    #[must_use]
+    pub const fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Integer(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_bitmap(&self) -> Option<&Bitmap> {
+        match self {
+            Self::Bitmap(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+impl Bitmap {
+    /// Builds a bitmap from row-major `pixels`, `width` pixels per row.
+    ///
+    /// # Panics
+    /// If `pixels` does not fill a whole number of rows.
+    #[must_use]
+    pub fn new(width: usize, pixels: Vec<bool>) -> Self {
+        assert!(
+            width > 0 && pixels.len().is_multiple_of(width),
+            "{} pixels do not fit in rows of {width}",
+            pixels.len()
+        );
+        Self { width, pixels }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.pixels.len() / self.width
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        if x >= self.width {
+            return None;
+        }
+        self.pixels.get(y * self.width + x).copied()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[bool]> {
+        self.pixels.chunks(self.width)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+            Self::Bitmap(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl Display for Bitmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for &pixel in row {
+                write!(f, "{}", if pixel { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Self::Integer(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<Bitmap> for Answer {
+    fn from(b: Bitmap) -> Self {
+        Self::Bitmap(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(2_713_310_158_u64).to_string(), "2713310158");
+        assert_eq!(Answer::from(-3_isize).to_string(), "-3");
+        assert_eq!(Answer::from("NDP").to_string(), "NDP");
+
+        let bitmap = Bitmap::new(3, vec![true, false, true, false, true, false]);
+        assert_eq!(Answer::from(bitmap).to_string(), "#.#\n.#.");
+    }
+
+    #[test]
+    fn test_accessors() {
+        let answer = Answer::from(13_usize);
+        assert_eq!(answer.as_integer().map(|n| n * 2), Some(26));
+        assert_eq!(answer.as_text(), None);
+
+        let bitmap = Bitmap::new(2, vec![true, false, false, true]);
+        assert_eq!(bitmap.height(), 2);
+        assert_eq!(bitmap.get(1, 1), Some(true));
+        assert_eq!(bitmap.get(2, 0), None);
+        assert_eq!(Answer::from(bitmap.clone()).as_bitmap(), Some(&bitmap));
+    }
+
+    #[test]
+    #[should_panic(expected = "do not fit")]
+    fn test_bitmap_ragged() {
+        let _ = Bitmap::new(2, vec![true; 3]);
+    }
+}
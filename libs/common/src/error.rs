@@ -0,0 +1,142 @@
+use std::fmt::Display;
+
+/// Why a day could not be solved, pointing at the offending input line when
+/// there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub day: Option<u8>,
+    /// 1-based line number of the input.
+    pub line: Option<usize>,
+    /// 1-based column of the line, in characters.
+    pub column: Option<usize>,
+    /// Text of the offending line, shown with a caret under the column.
+    pub excerpt: Option<String>,
+    pub reason: String,
+}
+
+pub type SolveResult<T> = Result<T, SolveError>;
+
+impl SolveError {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column: None,
+            excerpt: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Error for the line at the 0-based `idx`, as yielded by `enumerate`.
+    pub fn at_line(idx: usize, reason: impl Into<String>) -> Self {
+        Self::new(reason).with_line(idx + 1)
+    }
+
+    /// Sets the 1-based line number, unless one is already set.
+    #[must_use]
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Sets the 1-based column and the text of the offending line, unless a
+    /// column is already set.
+    #[must_use]
+    pub fn with_column(mut self, column: usize, excerpt: impl Into<String>) -> Self {
+        if self.column.is_none() {
+            self.column = Some(column);
+            self.excerpt = Some(excerpt.into());
+        }
+        self
+    }
+
+    /// Shifts the line number by `offset`, for errors raised while parsing
+    /// a block that starts in the middle of the input.
+    #[must_use]
+    pub fn offset_line(mut self, offset: usize) -> Self {
+        if let Some(line) = &mut self.line {
+            *line += offset;
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn for_day(mut self, day: u8) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = [
+            self.day.map(|day| format!("day {day}")),
+            self.line.map(|line| format!("line {line}")),
+            self.column.map(|column| format!("column {column}")),
+        ];
+        let location = location.into_iter().flatten().collect::<Vec<_>>();
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+        write!(f, "{}", self.reason)?;
+        if let (Some(column), Some(excerpt)) = (self.column, &self.excerpt) {
+            write!(f, "\n  {excerpt}\n  {:>column$}", "^")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<String> for SolveError {
+    fn from(reason: String) -> Self {
+        Self::new(reason)
+    }
+}
+
+impl From<&str> for SolveError {
+    fn from(reason: &str) -> Self {
+        Self::new(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = SolveError::at_line(2, "invalid range").for_day(4);
+        assert_eq!(error.to_string(), "day 4, line 3: invalid range");
+        assert_eq!(SolveError::new("no elves").to_string(), "no elves");
+        assert_eq!(
+            SolveError::new("no elves").for_day(1).to_string(),
+            "day 1: no elves"
+        );
+    }
+
+    #[test]
+    fn test_display_excerpt() {
+        let error = SolveError::new("expected a number")
+            .with_line(2)
+            .with_column(20, "  Starting items: 7x")
+            .for_day(11);
+        let expected = [
+            "day 11, line 2, column 20: expected a number",
+            "    Starting items: 7x",
+            "                     ^",
+        ];
+        assert_eq!(error.to_string(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_line_is_kept() {
+        let error = SolveError::at_line(0, "invalid")
+            .with_line(7)
+            .offset_line(10)
+            .for_day(1)
+            .for_day(2);
+        assert_eq!(error.line, Some(11));
+        assert_eq!(error.day, Some(1));
+    }
+}
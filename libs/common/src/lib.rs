@@ -0,0 +1,73 @@
+mod answer;
+mod error;
+mod normalize;
+mod registry;
+mod stream;
+
+pub use answer::{Answer, Bitmap};
+pub use error::{SolveError, SolveResult};
+pub use normalize::{normalize, skip_bom, Normalization};
+pub use registry::{Entry, Parsed, Registry};
+pub use stream::for_each_line;
+
+use std::io::BufRead;
+
+/// A solved advent of code puzzle.
+///
+/// The input is parsed once by [`Solution::parse`] and shared by both parts,
+/// so the parse step can be measured on its own.
+pub trait Solution {
+    /// Day of the advent calendar, starting at 1.
+    const DAY: u8;
+    /// Puzzle title as shown on the advent of code website.
+    const TITLE: &'static str;
+    /// Clean-up applied to the input before [`Solution::parse`].
+    const NORMALIZATION: Normalization = Normalization::Full;
+
+    /// Parsed puzzle input shared by both parts.
+    type Input: 'static;
+
+    /// # Errors
+    /// If the input is malformed.
+    fn parse(input: &str) -> SolveResult<Self::Input>;
+
+    /// # Errors
+    /// If the input has no answer for part a.
+    fn part_a(input: &Self::Input) -> SolveResult<Answer>;
+
+    /// # Errors
+    /// If the input has no answer for part b.
+    fn part_b(input: &Self::Input) -> SolveResult<Answer>;
+
+    /// Normalizes and parses the raw input and solves part a.
+    ///
+    /// # Errors
+    /// If the input is malformed or has no answer, tagged with [`Solution::DAY`].
+    fn solve_a(input: &str) -> SolveResult<Answer> {
+        Self::parse(&normalize(input, Self::NORMALIZATION))
+            .and_then(|input| Self::part_a(&input))
+            .map_err(|e| e.for_day(Self::DAY))
+    }
+
+    /// Normalizes and parses the raw input and solves part b.
+    ///
+    /// # Errors
+    /// If the input is malformed or has no answer, tagged with [`Solution::DAY`].
+    fn solve_b(input: &str) -> SolveResult<Answer> {
+        Self::parse(&normalize(input, Self::NORMALIZATION))
+            .and_then(|input| Self::part_b(&input))
+            .map_err(|e| e.for_day(Self::DAY))
+    }
+
+    /// Solves part a while reading the input, in constant memory. `None` if
+    /// the day needs the whole input at once.
+    fn stream_a(_reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        None
+    }
+
+    /// Solves part b while reading the input, in constant memory. `None` if
+    /// the day needs the whole input at once.
+    fn stream_b(_reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        None
+    }
+}
@@ -0,0 +1,104 @@
+use std::{borrow::Cow, io::BufRead};
+
+const BOM: &str = "\u{feff}";
+
+/// How an input is cleaned up before [`crate::Solution::parse`] sees it, so
+/// files saved by other editors give the same answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Drops a leading UTF-8 BOM, turns CRLF into LF, trims trailing
+    /// whitespace from every line and drops trailing blank lines, keeping at
+    /// most one final line ending.
+    Full,
+    /// Only drops the BOM and turns CRLF into LF, for days where spaces are
+    /// meaningful, like the fixed columns of the day 5 drawing.
+    LineEndings,
+    /// Leaves the input untouched.
+    Raw,
+}
+
+/// `input` cleaned up as `normalization` asks, borrowed when nothing changes.
+#[must_use]
+pub fn normalize(input: &str, normalization: Normalization) -> Cow<'_, str> {
+    let without_bom = input.strip_prefix(BOM).unwrap_or(input);
+    match normalization {
+        Normalization::Raw => Cow::Borrowed(input),
+        Normalization::LineEndings if without_bom.contains('\r') => {
+            Cow::Owned(without_bom.replace("\r\n", "\n"))
+        }
+        Normalization::LineEndings => Cow::Borrowed(without_bom),
+        Normalization::Full => {
+            let input = without_bom;
+            let content = input.trim_end();
+            let mut out = String::with_capacity(content.len() + 1);
+            for (i, line) in content.lines().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                out.push_str(line.trim_end());
+            }
+            if !content.is_empty() && input[content.len()..].contains('\n') {
+                out.push('\n');
+            }
+            if out == input {
+                Cow::Borrowed(input)
+            } else {
+                Cow::Owned(out)
+            }
+        }
+    }
+}
+
+/// Skips a UTF-8 BOM at the start of `reader`, for days reading raw bytes.
+///
+/// # Errors
+/// If `reader` fails.
+pub fn skip_bom(reader: &mut dyn BufRead) -> std::io::Result<()> {
+    if reader.fill_buf()?.starts_with(BOM.as_bytes()) {
+        reader.consume(BOM.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full() {
+        let full = |input| normalize(input, Normalization::Full);
+        assert_eq!(
+            full("\u{feff}1\r\n2  \r\n\r\n3\t\r\n\r\n \n"),
+            "1\n2\n\n3\n"
+        );
+        assert_eq!(full("1\n2"), "1\n2");
+        assert_eq!(full("  \n\n"), "");
+        assert!(matches!(full("1\n\n2\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let line_endings = |input| normalize(input, Normalization::LineEndings);
+        assert_eq!(
+            line_endings("\u{feff}    [D]    \r\n[N]\r\n\n"),
+            "    [D]    \n[N]\n\n"
+        );
+        assert!(matches!(line_endings("    [D]    \n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_raw() {
+        let input = "\u{feff}1\r\n";
+        assert_eq!(normalize(input, Normalization::Raw), input);
+    }
+
+    #[test]
+    fn test_skip_bom() {
+        let mut reader = "\u{feff}abc".as_bytes();
+        skip_bom(&mut reader).unwrap();
+        assert_eq!(reader, b"abc");
+        let mut reader = "abc".as_bytes();
+        skip_bom(&mut reader).unwrap();
+        assert_eq!(reader, b"abc");
+    }
+}
@@ -0,0 +1,243 @@
+use std::{any::Any, io::BufRead};
+
+use crate::{normalize, Answer, Solution, SolveResult};
+
+/// Type erased [`Solution::Input`].
+pub type Parsed = Box<dyn Any>;
+
+/// Type erased [`Solution`], so days with different inputs can live together.
+pub struct Entry {
+    pub day: u8,
+    pub title: &'static str,
+    parse: fn(&str) -> SolveResult<Parsed>,
+    part_a: fn(&Parsed) -> SolveResult<Answer>,
+    part_b: fn(&Parsed) -> SolveResult<Answer>,
+    stream_a: fn(&mut dyn BufRead) -> Option<SolveResult<Answer>>,
+    stream_b: fn(&mut dyn BufRead) -> Option<SolveResult<Answer>>,
+}
+
+/// Every registered day, ordered by day number.
+#[derive(Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Entry {
+    #[must_use]
+    pub fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            title: S::TITLE,
+            parse: |input| {
+                S::parse(&normalize(input, S::NORMALIZATION))
+                    .map(|parsed| Box::new(parsed) as Parsed)
+                    .map_err(|e| e.for_day(S::DAY))
+            },
+            part_a: |parsed| S::part_a(downcast::<S>(parsed)).map_err(|e| e.for_day(S::DAY)),
+            part_b: |parsed| S::part_b(downcast::<S>(parsed)).map_err(|e| e.for_day(S::DAY)),
+            stream_a: |reader| S::stream_a(reader).map(|r| r.map_err(|e| e.for_day(S::DAY))),
+            stream_b: |reader| S::stream_b(reader).map(|r| r.map_err(|e| e.for_day(S::DAY))),
+        }
+    }
+
+    /// # Errors
+    /// If the input is malformed.
+    pub fn parse(&self, input: &str) -> SolveResult<Parsed> {
+        (self.parse)(input)
+    }
+
+    /// # Errors
+    /// If the input has no answer for part a.
+    ///
+    /// # Panics
+    /// If `parsed` was not produced by this entry's [`Entry::parse`].
+    pub fn part_a(&self, parsed: &Parsed) -> SolveResult<Answer> {
+        (self.part_a)(parsed)
+    }
+
+    /// # Errors
+    /// If the input has no answer for part b.
+    ///
+    /// # Panics
+    /// If `parsed` was not produced by this entry's [`Entry::parse`].
+    pub fn part_b(&self, parsed: &Parsed) -> SolveResult<Answer> {
+        (self.part_b)(parsed)
+    }
+
+    /// See [`Solution::stream_a`].
+    ///
+    /// # Errors
+    /// If the input is malformed, unreadable or has no answer for part a.
+    pub fn stream_a(&self, reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        (self.stream_a)(reader)
+    }
+
+    /// See [`Solution::stream_b`].
+    ///
+    /// # Errors
+    /// If the input is malformed, unreadable or has no answer for part b.
+    pub fn stream_b(&self, reader: &mut dyn BufRead) -> Option<SolveResult<Answer>> {
+        (self.stream_b)(reader)
+    }
+
+    /// # Errors
+    /// If the input is malformed or has no answer for part a.
+    pub fn solve_a(&self, input: &str) -> SolveResult<Answer> {
+        self.part_a(&self.parse(input)?)
+    }
+
+    /// # Errors
+    /// If the input is malformed or has no answer for part b.
+    pub fn solve_b(&self, input: &str) -> SolveResult<Answer> {
+        self.part_b(&self.parse(input)?)
+    }
+}
+
+impl Registry {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Registers `S`, keeping the entries ordered by day.
+    ///
+    /// # Panics
+    /// If another solution is already registered for the same day.
+    #[must_use]
+    pub fn with<S: Solution>(mut self) -> Self {
+        match self.entries.binary_search_by_key(&S::DAY, |e| e.day) {
+            Ok(_) => panic!("day {} registered twice", S::DAY),
+            Err(idx) => self.entries.insert(idx, Entry::of::<S>()),
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn get(&self, day: u8) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.day == day)
+    }
+
+    #[must_use]
+    pub fn last(&self) -> Option<&Entry> {
+        self.entries.last()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Entry> {
+        self.entries.iter()
+    }
+}
+
+fn downcast<S: Solution>(parsed: &Parsed) -> &S::Input {
+    parsed
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("input was not parsed by day {}", S::DAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SolveError;
+
+    struct Words;
+    struct Lines;
+
+    impl Solution for Words {
+        const DAY: u8 = 2;
+        const TITLE: &'static str = "Words";
+        type Input = Vec<String>;
+
+        fn parse(input: &str) -> SolveResult<Self::Input> {
+            Ok(input.split_whitespace().map(ToString::to_string).collect())
+        }
+
+        fn part_a(input: &Self::Input) -> SolveResult<Answer> {
+            Ok(input.len().into())
+        }
+
+        fn part_b(input: &Self::Input) -> SolveResult<Answer> {
+            input.iter().position(|w| w == "!").map_or_else(
+                || Ok(input.concat().into()),
+                |i| Err(SolveError::at_line(i, "bang")),
+            )
+        }
+    }
+
+    impl Solution for Lines {
+        const DAY: u8 = 1;
+        const TITLE: &'static str = "Lines";
+        type Input = usize;
+
+        fn parse(input: &str) -> SolveResult<Self::Input> {
+            Ok(input.lines().count())
+        }
+
+        fn part_a(input: &Self::Input) -> SolveResult<Answer> {
+            Ok((*input).into())
+        }
+
+        fn part_b(input: &Self::Input) -> SolveResult<Answer> {
+            Ok((input * 2).into())
+        }
+
+        fn stream_a(reader: &mut dyn std::io::BufRead) -> Option<SolveResult<Answer>> {
+            Some(
+                reader
+                    .lines()
+                    .try_fold(0, |n, line| line.map(|_| n + 1))
+                    .map(Answer::from)
+                    .map_err(|_| SolveError::at_line(0, "unreadable")),
+            )
+        }
+    }
+
+    #[test]
+    fn test_registry_order() {
+        let registry = Registry::new().with::<Words>().with::<Lines>();
+        let days = registry.iter().map(|e| e.day).collect::<Vec<_>>();
+        assert_eq!(days, [1, 2]);
+        assert_eq!(registry.last().map(|e| e.title), Some("Words"));
+    }
+
+    #[test]
+    fn test_entry_solve() {
+        let registry = Registry::new().with::<Words>().with::<Lines>();
+        let words = registry.get(2).expect("day 2 is registered");
+        assert_eq!(words.solve_a("a b\nc"), Ok(3.into()));
+        assert_eq!(words.solve_b("a b\nc"), Ok("abc".into()));
+
+        let lines = registry.get(1).expect("day 1 is registered");
+        let parsed = lines.parse("a b\nc").expect("lines never fail to parse");
+        assert_eq!(lines.part_a(&parsed), Ok(2.into()));
+        assert_eq!(lines.part_b(&parsed), Ok(4.into()));
+    }
+
+    #[test]
+    fn test_entry_stream() {
+        let lines = Entry::of::<Lines>();
+        assert_eq!(
+            lines.stream_a(&mut "a\nb\nc".as_bytes()),
+            Some(Ok(3.into()))
+        );
+        assert_eq!(lines.stream_b(&mut "a".as_bytes()), None);
+
+        let error = lines.stream_a(&mut [0xff].as_slice()).unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "day 1, line 1: unreadable");
+        assert_eq!(Entry::of::<Words>().stream_a(&mut "a".as_bytes()), None);
+    }
+
+    #[test]
+    fn test_entry_error_has_day() {
+        let entry = Entry::of::<Words>();
+        let error = entry.solve_b("a ! b").expect_err("bang is rejected");
+        assert_eq!(error.to_string(), "day 2, line 2: bang");
+        assert_eq!(Words::solve_b("a ! b"), Err(error));
+    }
+
+    #[test]
+    #[should_panic(expected = "registered twice")]
+    fn test_registry_duplicate() {
+        let _ = Registry::new().with::<Words>().with::<Words>();
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::BufRead;
+
+use crate::{SolveError, SolveResult};
+
+/// Calls `f` with the 0-based index and content of every line of `reader`,
+/// cleaned up like [`crate::Normalization::Full`] does: without its line ending,
+/// trailing whitespace or a leading BOM. A single buffer is reused for all
+/// lines, so memory stays constant however long the input is.
+///
+/// # Errors
+/// The first error of `f`, or if `reader` fails or is not valid UTF-8.
+pub fn for_each_line(
+    reader: &mut dyn BufRead,
+    mut f: impl FnMut(usize, &str) -> SolveResult<()>,
+) -> SolveResult<()> {
+    let mut line = String::new();
+    for i in 0.. {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| SolveError::at_line(i, format!("failed to read input: {e}")))?;
+        if read == 0 {
+            break;
+        }
+        let content = if i == 0 {
+            line.strip_prefix('\u{feff}').unwrap_or(&line)
+        } else {
+            &line
+        };
+        f(i, content.trim_end())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_line() {
+        let mut lines = Vec::new();
+        let result = for_each_line(&mut "\u{feff}a \r\n\nb".as_bytes(), |i, line| {
+            lines.push((i, line.to_string()));
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            lines,
+            [(0, "a".into()), (1, String::new()), (2, "b".into())]
+        );
+
+        let error = for_each_line(&mut "a\nb\n".as_bytes(), |i, _| {
+            if i == 1 {
+                return Err(SolveError::at_line(i, "no b"));
+            }
+            Ok(())
+        });
+        assert_eq!(error.unwrap_err().to_string(), "line 2: no b");
+    }
+}
@@ -0,0 +1,16 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// `size` elves carrying 1 to 10 snacks each.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for elf in 0..size.max(3) {
+        if elf > 0 {
+            writeln!(out)?;
+        }
+        for _ in 0..rng.range(1..=10) {
+            writeln!(out, "{}", rng.range(1_000..=60_000))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// `size` rounds.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for _ in 0..size {
+        writeln!(
+            out,
+            "{} {}",
+            rng.pick(&['A', 'B', 'C']),
+            rng.pick(&['X', 'Y', 'Z'])
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+const ITEMS: &[u8; 52] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const GROUP_SIZE: usize = 3;
+
+/// `size` groups of 3 rucksacks.
+///
+/// The items of a group are split into a badge and a private pool per
+/// rucksack, so the badge is the only item all of them share. Each pool is
+/// split again between both compartments, which only share one item.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut items = *ITEMS;
+    for _ in 0..size {
+        rng.shuffle(&mut items);
+        let (badge, pools) = items.split_first().expect("there are 52 items");
+        for pool in pools.chunks(pools.len() / GROUP_SIZE) {
+            let (left, right) = pool.split_at(pool.len() / 2);
+            let shared = if rng.one_in(2) {
+                *badge
+            } else {
+                *rng.pick(pool)
+            };
+            let len = rng.below(12) + 2;
+            let mut compartments = [left, right].map(|items| {
+                let mut compartment = (0..len).map(|_| *rng.pick(items)).collect::<Vec<_>>();
+                compartment[0] = shared;
+                compartment
+            });
+            if shared != *badge {
+                compartments[rng.below(2)][1] = *badge;
+            }
+            for compartment in &mut compartments {
+                rng.shuffle(compartment);
+                out.write_all(compartment)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// `size` pairs of section ranges.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut range = || {
+        let start = rng.range(1..=99);
+        (start, rng.range(start..=99))
+    };
+    for _ in 0..size {
+        let ((a, b), (c, d)) = (range(), range());
+        writeln!(out, "{a}-{b},{c}-{d}")?;
+    }
+    Ok(())
+}
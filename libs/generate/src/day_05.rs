@@ -0,0 +1,47 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// The stack ids are single digits.
+const STACKS: usize = 9;
+
+/// `size` crates spread over 9 stacks, then `size` procedures.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut heights = [0; STACKS];
+    for _ in 0..size.max(1) {
+        heights[rng.below(STACKS)] += 1;
+    }
+
+    let top = heights.iter().copied().max().unwrap_or(0);
+    for level in (0..top).rev() {
+        let row = heights
+            .iter()
+            .map(|&height| {
+                if height > level {
+                    format!("[{}]", char::from(b'A' + rng.below(26) as u8))
+                } else {
+                    "   ".to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", row.join(" "))?;
+    }
+    let ids = (1..=STACKS).map(|id| format!(" {id} ")).collect::<Vec<_>>();
+    writeln!(out, "{}\n", ids.join(" "))?;
+
+    for _ in 0..size {
+        let sources = (0..STACKS).filter(|&s| heights[s] > 0).collect::<Vec<_>>();
+        let source = *rng.pick(&sources);
+        let destination = (source + 1 + rng.below(STACKS - 1)) % STACKS;
+        let quantity = rng.below(heights[source].min(10)) + 1;
+        heights[source] -= quantity;
+        heights[destination] += quantity;
+        writeln!(
+            out,
+            "move {quantity} from {} to {}",
+            source + 1,
+            destination + 1
+        )?;
+    }
+    Ok(())
+}
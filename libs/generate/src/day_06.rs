@@ -0,0 +1,15 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// A stream of `size` characters repeating few enough letters that neither
+/// marker shows up, then both markers at the very end.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for _ in 0..size {
+        out.write_all(&[*rng.pick(b"abc")])?;
+    }
+    let mut markers = *b"defghijklmnopqrstuvwxyz";
+    rng.shuffle(&mut markers);
+    out.write_all(&markers[..14])?;
+    writeln!(out)
+}
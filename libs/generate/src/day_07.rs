@@ -0,0 +1,39 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// A terminal transcript exploring `size` folders with 0 to 4 files each.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    // Attaching every folder to a random earlier one keeps the tree shallow.
+    let mut children = vec![Vec::new(); size.max(1)];
+    for folder in 1..children.len() {
+        let parent = rng.below(folder);
+        children[parent].push(folder);
+    }
+    writeln!(out, "$ cd /")?;
+    explore(rng, &children, 0, out)
+}
+
+fn explore(
+    rng: &mut Rng,
+    children: &[Vec<usize>],
+    folder: usize,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(out, "$ ls")?;
+    for child in &children[folder] {
+        writeln!(out, "dir d{child}")?;
+    }
+    for file in 0..rng.below(5) {
+        let extension = rng.pick(&["", ".txt", ".dat", ".log"]);
+        // Mostly small files, with a few large enough to fill the disk.
+        let max = 10_i64.pow(rng.range(3..=7) as u32);
+        writeln!(out, "{} f{file}{extension}", rng.range(1..=max))?;
+    }
+    for &child in &children[folder] {
+        writeln!(out, "$ cd d{child}")?;
+        explore(rng, children, child, out)?;
+        writeln!(out, "$ cd ..")?;
+    }
+    Ok(())
+}
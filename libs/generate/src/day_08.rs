@@ -0,0 +1,16 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// A square forest of about `size` trees.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let side = size.isqrt().max(1);
+    for _ in 0..side {
+        let row = (0..side)
+            .map(|_| b'0' + rng.below(10) as u8)
+            .collect::<Vec<_>>();
+        out.write_all(&row)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// `size` moves of the head of the rope.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for _ in 0..size {
+        writeln!(
+            out,
+            "{} {}",
+            rng.pick(&['U', 'R', 'D', 'L']),
+            rng.range(1..=20)
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// The CRT needs 240 cycles.
+const MIN_CYCLES: usize = 240;
+
+/// A program running for `size` cycles, keeping `X` around the screen.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut x = 1;
+    let mut cycles = 0;
+    while cycles < size.max(MIN_CYCLES) {
+        if rng.one_in(3) {
+            writeln!(out, "noop")?;
+            cycles += 1;
+        } else {
+            let value = rng.range(-5 - x.min(0)..=5 - (x - 40).max(0));
+            x += value;
+            writeln!(out, "addx {value}")?;
+            cycles += 2;
+        }
+    }
+    Ok(())
+}
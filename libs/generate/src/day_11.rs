@@ -0,0 +1,120 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+const DIVISORS: [u64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+/// Rounds of part a, whose worry levels must fit in a `u64`.
+const ROUNDS: usize = 20;
+
+struct Monkey {
+    items: Vec<u64>,
+    operation: Operation,
+    divisible_by: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Square,
+    Multiply(u64),
+    Add(u64),
+}
+
+/// `size` monkeys holding 1 to 6 items each.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let count = size.max(2);
+    let mut monkeys = (0..count)
+        .map(|id| monkey(rng, id, count))
+        .collect::<Vec<_>>();
+    // Multiplying monkeys passing items around can overflow part a, so they
+    // are turned into adding ones, all those of a game at once, until it fits.
+    while patch_overflowing(rng, &mut monkeys) {}
+
+    for (id, monkey) in monkeys.iter().enumerate() {
+        if id > 0 {
+            writeln!(out)?;
+        }
+        let items = monkey
+            .items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let operation = match monkey.operation {
+            Operation::Square => "old * old".to_string(),
+            Operation::Multiply(m) => format!("old * {m}"),
+            Operation::Add(a) => format!("old + {a}"),
+        };
+        writeln!(out, "Monkey {id}:")?;
+        writeln!(out, "  Starting items: {}", items.join(", "))?;
+        writeln!(out, "  Operation: new = {operation}")?;
+        writeln!(out, "  Test: divisible by {}", monkey.divisible_by)?;
+        writeln!(out, "    If true: throw to monkey {}", monkey.if_true)?;
+        writeln!(out, "    If false: throw to monkey {}", monkey.if_false)?;
+    }
+    Ok(())
+}
+
+fn monkey(rng: &mut Rng, id: usize, count: usize) -> Monkey {
+    let items = (0..rng.range(1..=6))
+        .map(|_| rng.range(50..=99) as u64)
+        .collect();
+    let operation = match rng.below(10) {
+        0 => Operation::Square,
+        1..=4 => Operation::Multiply(rng.range(2..=19) as u64),
+        _ => Operation::Add(rng.range(1..=9) as u64),
+    };
+    let divisible_by = *rng.pick(&DIVISORS);
+    // Never throw to itself.
+    let mut other = || (id + 1 + rng.below(count - 1)) % count;
+    Monkey {
+        items,
+        operation,
+        divisible_by,
+        if_true: other(),
+        if_false: other(),
+    }
+}
+
+/// Plays part a, turning every monkey whose operation overflows into an
+/// adding one on the spot, and whether any was. Patching changes where later
+/// items go, so the whole game must be played again until none overflows.
+fn patch_overflowing(rng: &mut Rng, monkeys: &mut [Monkey]) -> bool {
+    let mut items = monkeys.iter().map(|m| m.items.clone()).collect::<Vec<_>>();
+    let mut patched = false;
+    for _ in 0..ROUNDS {
+        for id in 0..monkeys.len() {
+            for item in std::mem::take(&mut items[id]) {
+                let monkey = &mut monkeys[id];
+                let worry = match monkey.operation.apply(item) {
+                    Some(worry) => worry,
+                    None => {
+                        monkey.operation = Operation::Add(rng.range(1..=9) as u64);
+                        patched = true;
+                        monkey
+                            .operation
+                            .apply(item)
+                            .expect("worry levels are divided by 3, so adding to them fits")
+                    }
+                } / 3;
+                let dest = if worry % monkey.divisible_by == 0 {
+                    monkey.if_true
+                } else {
+                    monkey.if_false
+                };
+                items[dest].push(worry);
+            }
+        }
+    }
+    patched
+}
+
+impl Operation {
+    fn apply(self, item: u64) -> Option<u64> {
+        match self {
+            Self::Square => item.checked_mul(item),
+            Self::Multiply(m) => item.checked_mul(m),
+            Self::Add(a) => item.checked_add(a),
+        }
+    }
+}
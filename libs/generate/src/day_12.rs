@@ -0,0 +1,35 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// One row must fit the start, the climb from `a` to `z` and the destination.
+const MIN_SIDE: usize = 28;
+
+/// A square map of about `size` squares, with a row climbing steadily from the
+/// start on the left to the destination on the right, so there is always a
+/// path. The other squares roughly follow the climb, leaving many other
+/// routes open.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let side = size.isqrt().max(MIN_SIDE);
+    let path_row = rng.below(side);
+    // The start counts as `a` and the destination as `z`, so the climb may
+    // begin one above and end one below them.
+    let (low, high) = (rng.range(0..=1), rng.range(24..=25));
+    let climb = |x: usize| {
+        let x = x.clamp(1, side - 2) as i64 - 1;
+        low + x * (high - low) / (side as i64 - 3)
+    };
+    for y in 0..side {
+        let row = (0..side)
+            .map(|x| match (y == path_row, x) {
+                (true, 0) => b'S',
+                (true, x) if x == side - 1 => b'E',
+                (true, x) => b'a' + climb(x) as u8,
+                (false, x) => b'a' + (climb(x) + rng.range(-3..=1)).clamp(0, 25) as u8,
+            })
+            .collect::<Vec<_>>();
+        out.write_all(&row)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// `size` pairs of packets nested up to 4 levels deep.
+///
+/// Both packets of a pair start with a different number, so they are never
+/// equal. No packet is a lone `[2]` or `[6]`, which would tie with a divider.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for pair in 0..size.max(1) {
+        if pair > 0 {
+            writeln!(out)?;
+        }
+        let first = rng.range(0..=10);
+        let second = (first + rng.range(1..=10)) % 11;
+        for number in [first, second] {
+            let mut packet = format!("[{number}");
+            let mut elements = rng.below(4);
+            if elements == 0 && matches!(number, 2 | 6) {
+                elements = 1;
+            }
+            for _ in 0..elements {
+                packet.push(',');
+                push_element(rng, 3, &mut packet);
+            }
+            packet.push(']');
+            writeln!(out, "{packet}")?;
+        }
+    }
+    Ok(())
+}
+
+fn push_element(rng: &mut Rng, depth: usize, packet: &mut String) {
+    if depth == 0 || rng.one_in(2) {
+        packet.push_str(&rng.range(0..=10).to_string());
+        return;
+    }
+    packet.push('[');
+    for i in 0..rng.below(4) {
+        if i > 0 {
+            packet.push(',');
+        }
+        push_element(rng, depth - 1, packet);
+    }
+    packet.push(']');
+}
@@ -0,0 +1,31 @@
+use std::io::{self, Write};
+
+use crate::Rng;
+
+/// Column the sand pours from.
+const SOURCE: i64 = 500;
+
+/// `size` rock paths of 2 to 5 points below the source, the cave getting
+/// deeper and wider as `size` grows.
+pub fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let depth = 10 + size as i64 / 4;
+    for _ in 0..size.max(1) {
+        let mut point = (SOURCE + rng.range(-depth..=depth), rng.range(2..=depth));
+        let mut points = vec![point];
+        for _ in 0..rng.range(1..=4) {
+            let length = rng.range(-6..=6);
+            point = if points.len() % 2 == 0 {
+                (point.0, (point.1 + length).clamp(2, depth))
+            } else {
+                (point.0 + length, point.1)
+            };
+            points.push(point);
+        }
+        let path = points
+            .iter()
+            .map(|(x, y)| format!("{x},{y}"))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", path.join(" -> "))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Deterministic puzzle inputs of any size, to benchmark the days and catch
+//! regressions on inputs far larger than the real ones.
+//!
+//! What `size` counts depends on the day, from elves to trees, and is
+//! raised to the smallest size that still has answers for both parts.
+
+mod day_01;
+mod day_02;
+mod day_03;
+mod day_04;
+mod day_05;
+mod day_06;
+mod day_07;
+mod day_08;
+mod day_09;
+mod day_10;
+mod day_11;
+mod day_12;
+mod day_13;
+mod day_14;
+mod rng;
+
+use std::io::{self, Write};
+
+pub use rng::Rng;
+
+/// Writes an input of `size` records drawn from the generator to `out`.
+pub type Generator = fn(&mut Rng, usize, &mut dyn Write) -> io::Result<()>;
+
+/// Generator of `day`, `None` if there is none.
+#[must_use]
+pub fn generator(day: u8) -> Option<Generator> {
+    Some(match day {
+        1 => day_01::generate,
+        2 => day_02::generate,
+        3 => day_03::generate,
+        4 => day_04::generate,
+        5 => day_05::generate,
+        6 => day_06::generate,
+        7 => day_07::generate,
+        8 => day_08::generate,
+        9 => day_09::generate,
+        10 => day_10::generate,
+        11 => day_11::generate,
+        12 => day_12::generate,
+        13 => day_13::generate,
+        14 => day_14::generate,
+        _ => return None,
+    })
+}
+
+/// Input of `day` for `size` and `seed`, `None` if the day has no generator.
+#[must_use]
+pub fn to_string(day: u8, size: usize, seed: u64) -> Option<String> {
+    let mut out = Vec::new();
+    generator(day)?(&mut Rng::new(seed), size, &mut out).expect("writing to a Vec never fails");
+    Some(String::from_utf8(out).expect("generators only write ASCII"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        for day in 1..=14 {
+            let input = to_string(day, 20, 3).unwrap();
+            assert!(!input.is_empty(), "day {day}");
+            assert!(input.is_ascii(), "day {day}");
+            assert_eq!(Some(&input), to_string(day, 20, 3).as_ref(), "day {day}");
+            assert_ne!(Some(&input), to_string(day, 20, 4).as_ref(), "day {day}");
+        }
+        assert_eq!(to_string(15, 20, 3), None);
+    }
+}
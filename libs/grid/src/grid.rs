@@ -0,0 +1,255 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use common::{SolveError, SolveResult};
+
+use crate::{Point, SignedPoint};
+
+/// A dense rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per character, skipping empty lines.
+    ///
+    /// # Errors
+    /// If `cell` rejects a character or the lines differ in length, with the
+    /// line number of the offending line.
+    pub fn parse(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, String>,
+    ) -> SolveResult<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+        for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let len = cells.len();
+            for c in line.chars() {
+                cells.push(cell(c).map_err(|e| SolveError::at_line(i, e))?);
+            }
+            let found = cells.len() - len;
+            let width = *width.get_or_insert(found);
+            if found != width {
+                return Err(SolveError::at_line(
+                    i,
+                    format!("Expected {width} cells, found {found}"),
+                ));
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn contains(&self, p: Point) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    /// Converts `p` to a cell of the grid, `None` if it falls outside.
+    #[must_use]
+    pub fn to_point(&self, p: SignedPoint) -> Option<Point> {
+        Point::try_from(p).ok().filter(|&p| self.contains(p))
+    }
+
+    #[must_use]
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.contains(p).then(|| &self.cells[self.idx(p)])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        let idx = self.idx(p);
+        self.contains(p).then(|| &mut self.cells[idx])
+    }
+
+    /// # Panics
+    /// If `y` is out of bounds.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} out of bounds");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + DoubleEndedIterator {
+        // `chunks` panics on 0, an empty grid has no rows anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Cells of column `x` from top to bottom.
+    ///
+    /// # Panics
+    /// If `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl ExactSizeIterator<Item = &T> + DoubleEndedIterator {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// Orthogonal neighbours of `p` inside of the grid.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours4().filter(|&n| self.contains(n))
+    }
+
+    /// Surrounding neighbours of `p` inside of the grid.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|&n| self.contains(n))
+    }
+
+    const fn idx(&self, p: Point) -> usize {
+        p.y * self.width + p.x
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    #[must_use]
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Grows the grid to at least `width` by `height`, filling the new cells
+    /// with `fill`. Never shrinks.
+    pub fn grow(&mut self, width: usize, height: usize, fill: T) {
+        let (width, height) = (width.max(self.width), height.max(self.height));
+        if width > self.width {
+            let mut cells = Vec::with_capacity(width * height);
+            for row in self.rows() {
+                cells.extend_from_slice(row);
+                cells.resize(cells.len() + width - self.width, fill.clone());
+            }
+            self.cells = cells;
+            self.width = width;
+        }
+        self.cells.resize(width * height, fill);
+        self.height = height;
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("{p:?} out of bounds"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p:?} out of bounds"))
+    }
+}
+
+/// One line per row, without a trailing newline.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> SolveResult<Grid<u32>> {
+        Grid::parse(input, |c| {
+            c.to_digit(10).ok_or_else(|| format!("not a digit: {c}"))
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let error = digits("12\n3x").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: not a digit: x");
+        let error = digits("12\n\n345").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: Expected 2 cells, found 3");
+    }
+
+    #[test]
+    fn test_slices() {
+        let grid = digits("123\n456\n789").unwrap();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6, 9]);
+        assert_eq!(grid.column(0).rev().copied().collect::<Vec<_>>(), [7, 4, 1]);
+        assert_eq!(grid.rows().len(), 3);
+        assert_eq!(grid.iter().nth(4), Some((Point::new(1, 1), &5)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let grid = Grid::new(2, 3, 0);
+        assert!(grid.contains(Point::new(1, 2)));
+        assert!(!grid.contains(Point::new(2, 0)));
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+        assert_eq!(
+            grid.to_point(SignedPoint::new(1, 1)),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(grid.to_point(SignedPoint::new(-1, 1)), None);
+        assert_eq!(grid.neighbours4(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_grow() {
+        let mut grid = digits("12\n34").unwrap();
+        grid.grow(3, 3, 0);
+        assert_eq!(grid.to_string(), "120\n340\n000");
+        grid.grow(1, 4, 9);
+        assert_eq!(grid.to_string(), "120\n340\n000\n999");
+
+        let mut empty = Grid::new(0, 0, '.');
+        empty.grow(2, 1, '#');
+        assert_eq!(empty.to_string(), "##");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_index_out_of_bounds() {
+        let _ = Grid::new(1, 1, 0)[Point::new(1, 0)];
+    }
+}
@@ -0,0 +1,188 @@
+use std::ops::{Add, Sub};
+
+/// Offsets of the 4 orthogonal neighbours: up, right, down, left.
+const NEIGHBOURS_4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the 8 surrounding neighbours, clockwise from the top left.
+const NEIGHBOURS_8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A cell of a [`Grid`](crate::Grid), `y` growing downwards.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// A point of an unbounded plane, which may go negative.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SignedPoint {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    #[must_use]
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Orthogonal neighbours, skipping the ones below zero.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        NEIGHBOURS_4
+            .into_iter()
+            .filter_map(move |offset| self.offset(offset))
+    }
+
+    /// Surrounding neighbours, skipping the ones below zero.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        NEIGHBOURS_8
+            .into_iter()
+            .filter_map(move |offset| self.offset(offset))
+    }
+
+    #[must_use]
+    pub const fn manhattan_distance(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    fn offset(self, (dx, dy): (i64, i64)) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(dx as isize)?,
+            y: self.y.checked_add_signed(dy as isize)?,
+        })
+    }
+}
+
+impl SignedPoint {
+    #[must_use]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Orthogonal neighbours: up, right, down, left.
+    #[must_use]
+    pub fn neighbours4(self) -> [Self; 4] {
+        NEIGHBOURS_4.map(|(x, y)| self + Self { x, y })
+    }
+
+    /// Surrounding neighbours, clockwise from the top left.
+    #[must_use]
+    pub fn neighbours8(self) -> [Self; 8] {
+        NEIGHBOURS_8.map(|(x, y)| self + Self { x, y })
+    }
+
+    #[must_use]
+    pub const fn manhattan_distance(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Number of king moves between both points.
+    #[must_use]
+    pub const fn chebyshev_distance(self, other: Self) -> u64 {
+        let (dx, dy) = (self.x.abs_diff(other.x), self.y.abs_diff(other.y));
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+
+    /// Smallest and largest coordinates of `points`, `None` if there are none.
+    pub fn bounds(points: impl IntoIterator<Item = Self>) -> Option<(Self, Self)> {
+        points.into_iter().fold(None, |bounds, p| {
+            let (min, max) = bounds.unwrap_or((p, p));
+            Some((
+                Self::new(min.x.min(p.x), min.y.min(p.y)),
+                Self::new(max.x.max(p.x), max.y.max(p.y)),
+            ))
+        })
+    }
+}
+
+impl Add for SignedPoint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for SignedPoint {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl From<Point> for SignedPoint {
+    fn from(p: Point) -> Self {
+        Self::new(p.x as i64, p.y as i64)
+    }
+}
+
+impl TryFrom<SignedPoint> for Point {
+    type Error = String;
+
+    fn try_from(p: SignedPoint) -> Result<Self, Self::Error> {
+        match (usize::try_from(p.x), usize::try_from(p.y)) {
+            (Ok(x), Ok(y)) => Ok(Self::new(x, y)),
+            _ => Err(format!("point {},{} is negative", p.x, p.y)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours() {
+        let origin = Point::new(0, 0);
+        assert_eq!(
+            origin.neighbours4().collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(Point::new(1, 1).neighbours8().count(), 8);
+
+        let p = SignedPoint::new(0, 0);
+        assert_eq!(p.neighbours4()[0], SignedPoint::new(0, -1));
+        assert_eq!(p.neighbours8()[0], SignedPoint::new(-1, -1));
+        assert!(p.neighbours8().iter().all(|n| n.chebyshev_distance(p) == 1));
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (SignedPoint::new(-2, 3), SignedPoint::new(1, -1));
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(a - b + b, a);
+        assert_eq!(Point::new(3, 0).manhattan_distance(Point::new(1, 2)), 4);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let points = [(1, -2), (-3, 4), (0, 0)].map(|(x, y)| SignedPoint::new(x, y));
+        assert_eq!(
+            SignedPoint::bounds(points),
+            Some((SignedPoint::new(-3, -2), SignedPoint::new(1, 4)))
+        );
+        assert_eq!(SignedPoint::bounds([]), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let p = Point::new(2, 5);
+        assert_eq!(Point::try_from(SignedPoint::from(p)), Ok(p));
+        assert!(Point::try_from(SignedPoint::new(0, -1)).is_err());
+    }
+}
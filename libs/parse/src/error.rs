@@ -0,0 +1,59 @@
+use common::SolveError;
+use nom_supreme::error::{BaseErrorKind, Expectation, GenericErrorTree, StackContext};
+
+use crate::{ParseError, Span};
+
+/// Converts the error tree into an error at the furthest point any branch
+/// reached, which is usually where the input actually broke.
+pub fn to_solve_error(input: &str, tree: &ParseError<'_>) -> SolveError {
+    let (location, reason) = furthest(tree);
+    let line = location.location_line() as usize;
+    let excerpt = input.lines().nth(line - 1).unwrap_or_default();
+    SolveError::new(reason)
+        .with_line(line)
+        .with_column(location.get_utf8_column(), excerpt)
+}
+
+fn furthest<'a>(tree: &ParseError<'a>) -> (Span<'a>, String) {
+    match tree {
+        GenericErrorTree::Base { location, kind } => (*location, describe(kind)),
+        GenericErrorTree::Stack { base, contexts } => {
+            let (location, reason) = furthest(base);
+            let context = contexts.iter().find_map(|(_, context)| match context {
+                StackContext::Context(name) => Some(*name),
+                StackContext::Kind(_) => None,
+            });
+            match context {
+                Some(name) => (location, format!("{reason} in {name}")),
+                None => (location, reason),
+            }
+        }
+        GenericErrorTree::Alt(alternatives) => {
+            let candidates = alternatives.iter().map(furthest).collect::<Vec<_>>();
+            let Some(offset) = candidates.iter().map(|(l, _)| l.location_offset()).max() else {
+                return (Span::new(""), "no alternative matched".to_string());
+            };
+            let mut reasons = Vec::new();
+            let mut location = None;
+            for (l, reason) in candidates {
+                if l.location_offset() == offset && !reasons.contains(&reason) {
+                    location.get_or_insert(l);
+                    reasons.push(reason);
+                }
+            }
+            (
+                location.expect("the furthest candidate exists"),
+                reasons.join(" or "),
+            )
+        }
+    }
+}
+
+fn describe<E: std::fmt::Display>(kind: &BaseErrorKind<&'static str, E>) -> String {
+    match kind {
+        BaseErrorKind::Expected(Expectation::CrLf) => "expected end of line".to_string(),
+        BaseErrorKind::Expected(expectation) => format!("expected {expectation}"),
+        BaseErrorKind::External(e) => e.to_string(),
+        BaseErrorKind::Kind(kind) => format!("error in {kind:?}"),
+    }
+}
@@ -0,0 +1,213 @@
+//! Parsing helpers shared by the days, built on `nom` with located spans so
+//! errors can point at the line and column where the input broke.
+
+mod error;
+
+use std::str::FromStr;
+
+use common::SolveResult;
+use nom::{
+    character::complete::{char, digit1, line_ending, multispace0, space0},
+    combinator::{all_consuming, cut, map_res, opt, recognize, value},
+    multi::many_m_n,
+    sequence::{pair, preceded, tuple},
+    IResult, Parser,
+};
+use nom_locate::LocatedSpan;
+use nom_supreme::{error::ErrorTree, ParserExt};
+
+pub use nom;
+/// Like `nom`'s `tag`, but errors name the expected tag.
+pub use nom_supreme::tag::complete::tag;
+
+pub type Span<'a> = LocatedSpan<&'a str>;
+pub type ParseError<'a> = ErrorTree<Span<'a>>;
+pub type ParseResult<'a, T> = IResult<Span<'a>, T, ParseError<'a>>;
+
+/// Runs `parser` over the whole of `input`.
+///
+/// # Errors
+/// If `parser` fails or leaves input behind, pointing at the furthest line
+/// and column it reached.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    parser: impl Parser<Span<'a>, T, ParseError<'a>>,
+) -> SolveResult<T> {
+    match all_consuming(parser)(Span::new(input)) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(error::to_solve_error(input, &e)),
+        Err(nom::Err::Incomplete(_)) => Err("unexpected end of input".into()),
+    }
+}
+
+/// An unsigned integer.
+///
+/// # Errors
+/// If there are no digits or the number does not fit in `T`.
+pub fn unsigned<T>(input: Span<'_>) -> ParseResult<'_, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    map_res(digit1, |digits: Span<'_>| digits.fragment().parse())
+        .context("unsigned integer")
+        .parse(input)
+}
+
+/// An integer with an optional leading `-`.
+///
+/// # Errors
+/// If there are no digits or the number does not fit in `T`.
+pub fn signed<T>(input: Span<'_>) -> ParseResult<'_, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    map_res(
+        recognize(pair(opt(char('-')), digit1)),
+        |digits: Span<'_>| digits.fragment().parse(),
+    )
+    .context("integer")
+    .parse(input)
+}
+
+/// One or more `item`s separated by `separator`, like `79, 98`. An item must
+/// follow every separator.
+pub fn list<'a, T>(
+    separator: &'static str,
+    item: impl Parser<Span<'a>, T, ParseError<'a>>,
+) -> impl FnMut(Span<'a>) -> ParseResult<'a, Vec<T>> {
+    let mut item = item;
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((rest, _)) = tag::<_, _, ParseError<'a>>(separator)(input) {
+            let (rest, next) = cut(|i| item.parse(i))(rest)?;
+            items.push(next);
+            input = rest;
+        }
+        Ok((input, items))
+    }
+}
+
+/// A `label: value` field, like `  Starting items: 79, 98`, ignoring the
+/// indentation before the label. Once the label matched, the value must
+/// follow.
+pub fn field<'a, T>(
+    label: &'static str,
+    value: impl Parser<Span<'a>, T, ParseError<'a>>,
+) -> impl FnMut(Span<'a>) -> ParseResult<'a, T> {
+    let mut parser =
+        preceded(tuple((space0, tag(label), char(':'), space0)), cut(value)).context(label);
+    move |input| parser.parse(input)
+}
+
+/// One or more `block`s separated by blank lines, ignoring the whitespace
+/// around them. Anything but whitespace after a blank line must be a block.
+pub fn blocks<'a, T>(
+    block: impl Parser<Span<'a>, T, ParseError<'a>>,
+) -> impl FnMut(Span<'a>) -> ParseResult<'a, Vec<T>> {
+    let mut block = block;
+    move |input| {
+        let (mut input, ()) = skip_whitespace(input)?;
+        let mut blocks = Vec::new();
+        loop {
+            let (rest, value) = cut(|i| block.parse(i))(input)?;
+            blocks.push(value);
+            // The block may have consumed the end of its last line.
+            let consumed = &input.fragment()[..rest.location_offset() - input.location_offset()];
+            let needed = if consumed.ends_with('\n') { 1 } else { 2 };
+            let Ok((rest, _)) = line_endings(needed)(rest) else {
+                let (rest, ()) = skip_whitespace(rest)?;
+                return Ok((rest, blocks));
+            };
+            let (rest, ()) = skip_whitespace(rest)?;
+            if rest.is_empty() {
+                return Ok((rest, blocks));
+            }
+            input = rest;
+        }
+    }
+}
+
+/// Runs `parser`, also returning the 1-based line where it started, to
+/// report errors found after parsing.
+pub fn located<'a, T>(
+    mut parser: impl Parser<Span<'a>, T, ParseError<'a>>,
+) -> impl FnMut(Span<'a>) -> ParseResult<'a, (usize, T)> {
+    move |input: Span<'a>| {
+        let line = input.location_line() as usize;
+        let (rest, value) = parser.parse(input)?;
+        Ok((rest, (line, value)))
+    }
+}
+
+/// At least `min` line endings.
+fn line_endings<'a>(min: usize) -> impl FnMut(Span<'a>) -> ParseResult<'a, Vec<Span<'a>>> {
+    many_m_n(min, usize::MAX, line_ending)
+}
+
+fn skip_whitespace(input: Span<'_>) -> ParseResult<'_, ()> {
+    value((), multispace0)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::sequence::{separated_pair, terminated};
+
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(parse_all("42", unsigned::<u8>), Ok(42));
+        assert_eq!(parse_all("-42", signed::<i32>), Ok(-42));
+        assert!(parse_all("-42", unsigned::<u32>).is_err());
+        assert!(parse_all("300", unsigned::<u8>).is_err());
+    }
+
+    #[test]
+    fn test_field_list() {
+        let parser = field("Starting items", list(", ", unsigned::<u64>));
+        assert_eq!(
+            parse_all("  Starting items: 79, 98", parser),
+            Ok(vec![79, 98])
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let block = || located(separated_pair(unsigned::<u32>, line_ending, unsigned));
+        let input = "\n1\n2\n\n\n3\n4\n";
+        assert_eq!(
+            parse_all(input, blocks(block())),
+            Ok(vec![(2, (1, 2)), (6, (3, 4))])
+        );
+        assert!(parse_all("1\n2\n\n3", blocks(block())).is_err());
+    }
+
+    #[test]
+    fn test_error_reason() {
+        let parser = nom::branch::alt((tag("* old"), tag("+ ")));
+        let error = parse_all("/ 2", parser).expect_err("no operation matches");
+        assert_eq!(error.reason, r#"expected "* old" or expected "+ ""#);
+
+        let error = parse_all("300", unsigned::<u8>).expect_err("300 does not fit");
+        assert_eq!(
+            error.reason,
+            "number too large to fit in target type in unsigned integer"
+        );
+    }
+
+    #[test]
+    fn test_error_location() {
+        let parser = blocks(terminated(
+            field("Starting items", list(", ", unsigned::<u64>)),
+            line_ending,
+        ));
+        let error = parse_all("Starting items: 1\n\nStarting items: 7, x\n", parser)
+            .expect_err("x is not a number");
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.column, Some(20));
+        assert_eq!(error.excerpt.as_deref(), Some("Starting items: 7, x"));
+    }
+}
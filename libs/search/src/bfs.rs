@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+
+use crate::{tree::TreeOf, Graph, Search};
+
+/// Breadth first search from all of `starts` at once for the nearest node
+/// matching `is_goal`, counting every step as 1.
+pub fn bfs<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+) -> Search<G::Node> {
+    let mut tree = TreeOf::<G>::new();
+    let mut queue = starts
+        .into_iter()
+        .filter_map(|start| tree.reach(start, None, 0))
+        .collect::<VecDeque<_>>();
+    let mut visited = 0;
+    while let Some(idx) = queue.pop_front() {
+        visited += 1;
+        if is_goal(tree.node(idx)) {
+            return Search {
+                path: Some(tree.path(idx)),
+                visited,
+            };
+        }
+        let cost = tree.cost(idx) + 1;
+        let node = tree.node(idx).clone();
+        for (next, _) in graph.successors(&node) {
+            // Nodes are reached in order of cost, so only new ones are queued.
+            queue.extend(tree.reach(next, Some(idx), cost));
+        }
+    }
+    Search {
+        path: None,
+        visited,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_fn, Cost};
+
+    use super::*;
+
+    /// Numbers reachable by adding 1 or doubling, up to 100.
+    fn numbers(n: &u64) -> Vec<(u64, Cost)> {
+        [n + 1, n * 2]
+            .into_iter()
+            .filter(|&n| n <= 100)
+            .map(|n| (n, 5))
+            .collect()
+    }
+
+    #[test]
+    fn test_path() {
+        let search = bfs(&from_fn(numbers), [1], |&n| n == 20);
+        let path = search.path.unwrap();
+        assert_eq!(path.nodes, [1, 2, 4, 5, 10, 20]);
+        assert_eq!(path.cost, 5);
+        assert!(search.visited > 5);
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let search = bfs(&from_fn(numbers), [3, 9, 50], |&n| n == 20);
+        assert_eq!(search.path.unwrap().nodes, [9, 10, 20]);
+
+        let search = bfs(&from_fn(numbers), [7, 20], |&n| n == 20);
+        assert_eq!(search.path.unwrap().nodes, [20]);
+        assert_eq!(search.visited, 2);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let search = bfs(&from_fn(numbers), [60], |&n| n < 60);
+        assert_eq!(search.path, None);
+        assert_eq!(search.visited, 41);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{tree::TreeOf, Cost, Graph, Search};
+
+/// Dijkstra's search from all of `starts` at once for the cheapest node
+/// matching `is_goal`.
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Search<G::Node> {
+    astar(graph, starts, is_goal, |_| 0)
+}
+
+/// A* search from all of `starts` at once for the cheapest node matching
+/// `is_goal`, guided by `heuristic`.
+///
+/// The path is the cheapest as long as `heuristic` never overestimates the
+/// remaining cost to a goal.
+pub fn astar<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    mut heuristic: impl FnMut(&G::Node) -> Cost,
+) -> Search<G::Node> {
+    let mut tree = TreeOf::<G>::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if let Some(idx) = tree.reach(start, None, 0) {
+            queue.push(Reverse((heuristic(tree.node(idx)), 0, idx)));
+        }
+    }
+    let mut visited = 0;
+    while let Some(Reverse((_, cost, idx))) = queue.pop() {
+        if cost > tree.cost(idx) {
+            // Reached again for less since it was queued.
+            continue;
+        }
+        visited += 1;
+        if is_goal(tree.node(idx)) {
+            return Search {
+                path: Some(tree.path(idx)),
+                visited,
+            };
+        }
+        let node = tree.node(idx).clone();
+        for (next, step) in graph.successors(&node) {
+            let cost = cost + step;
+            if let Some(next) = tree.reach(next, Some(idx), cost) {
+                queue.push(Reverse((cost + heuristic(tree.node(next)), cost, next)));
+            }
+        }
+    }
+    Search {
+        path: None,
+        visited,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_fn, FnGraph};
+
+    use super::*;
+
+    /// Walking right costs 1 and jumping 10 to the right costs 3.
+    fn line() -> FnGraph<u64, impl Fn(&u64) -> [(u64, Cost); 2]> {
+        from_fn(|&n: &u64| [(n + 1, 1), (n + 10, 3)])
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra(&line(), [0], |&n| n == 23);
+        let path = search.path.unwrap();
+        assert_eq!(path.cost, 9);
+        assert_eq!(path.nodes.len(), 6);
+        assert_eq!((*path.start(), *path.goal()), (0, 23));
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let search = dijkstra(&line(), [0, 12], |&n| n == 23);
+        assert_eq!(search.path.unwrap().nodes, [12, 13, 23]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let target = 95;
+        // Every 10 steps cost at least 3.
+        let heuristic = |&n: &u64| (target - n.min(target)) * 3 / 10;
+        let plain = dijkstra(&line(), [0], |&n| n == target);
+        let guided = astar(&line(), [0], |&n| n == target, heuristic);
+        assert_eq!(plain.path.as_ref().map(|p| p.cost), Some(32));
+        assert_eq!(guided.path.as_ref().map(|p| p.cost), Some(32));
+        assert!(guided.visited < plain.visited);
+    }
+}
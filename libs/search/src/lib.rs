@@ -0,0 +1,83 @@
+//! Shortest path searches over any graph defined by its successors.
+
+mod bfs;
+mod dijkstra;
+mod tree;
+
+use std::{hash::Hash, marker::PhantomData};
+
+pub use bfs::bfs;
+pub use dijkstra::{astar, dijkstra};
+
+/// Cost of a step or a whole path.
+pub type Cost = u64;
+
+/// A graph known only through the successors of each node, so it can be
+/// explored lazily without building its edges up front.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    /// Nodes reachable in one step from `node`, with the cost of the step.
+    /// [`bfs`] ignores the costs and counts every step as 1.
+    fn successors(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, Cost)>;
+}
+
+/// A [`Graph`] from a successor function, see [`from_fn`].
+pub struct FnGraph<N, F> {
+    successors: F,
+    node: PhantomData<fn() -> N>,
+}
+
+/// Builds a [`Graph`] from a function returning the successors of a node.
+pub const fn from_fn<N, I, F>(successors: F) -> FnGraph<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+    F: Fn(&N) -> I,
+{
+    FnGraph {
+        successors,
+        node: PhantomData,
+    }
+}
+
+impl<N, I, F> Graph for FnGraph<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+    F: Fn(&N) -> I,
+{
+    type Node = N;
+
+    fn successors(&self, node: &N) -> impl Iterator<Item = (N, Cost)> {
+        (self.successors)(node).into_iter()
+    }
+}
+
+/// A path from one of the starts to a goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub nodes: Vec<N>,
+    pub cost: Cost,
+}
+
+impl<N> Path<N> {
+    #[must_use]
+    pub fn start(&self) -> &N {
+        &self.nodes[0]
+    }
+
+    #[must_use]
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.nodes.len() - 1]
+    }
+}
+
+/// Result of a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search<N> {
+    /// Cheapest path to a goal, `None` if no goal is reachable.
+    pub path: Option<Path<N>>,
+    /// Number of nodes expanded before the search stopped.
+    pub visited: usize,
+}
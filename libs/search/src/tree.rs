@@ -0,0 +1,78 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{Cost, Graph, Path};
+
+/// Every node reached so far with the cheapest known way to reach it,
+/// indexed so the queues only hold `usize`s.
+pub struct Tree<N> {
+    nodes: Vec<Reached<N>>,
+    index: HashMap<N, usize>,
+}
+
+struct Reached<N> {
+    node: N,
+    parent: Option<usize>,
+    cost: Cost,
+}
+
+impl<N: Clone + Eq + std::hash::Hash> Tree<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Records that `node` can be reached for `cost` through `parent`.
+    /// Returns its index if that is the first or a cheaper way to reach it.
+    pub fn reach(&mut self, node: N, parent: Option<usize>, cost: Cost) -> Option<usize> {
+        match self.index.entry(node) {
+            Entry::Occupied(entry) => {
+                let idx = *entry.get();
+                let reached = &mut self.nodes[idx];
+                if cost >= reached.cost {
+                    return None;
+                }
+                reached.parent = parent;
+                reached.cost = cost;
+                Some(idx)
+            }
+            Entry::Vacant(entry) => {
+                let idx = self.nodes.len();
+                self.nodes.push(Reached {
+                    node: entry.key().clone(),
+                    parent,
+                    cost,
+                });
+                entry.insert(idx);
+                Some(idx)
+            }
+        }
+    }
+
+    pub fn node(&self, idx: usize) -> &N {
+        &self.nodes[idx].node
+    }
+
+    pub fn cost(&self, idx: usize) -> Cost {
+        self.nodes[idx].cost
+    }
+
+    /// Follows the parents back from `idx` to its start.
+    pub fn path(&self, idx: usize) -> Path<N> {
+        let mut nodes = Vec::new();
+        let mut current = Some(idx);
+        while let Some(idx) = current {
+            nodes.push(self.nodes[idx].node.clone());
+            current = self.nodes[idx].parent;
+        }
+        nodes.reverse();
+        Path {
+            nodes,
+            cost: self.cost(idx),
+        }
+    }
+}
+
+/// Shorthand for the tree of the nodes of `G`.
+pub type TreeOf<G> = Tree<<G as Graph>::Node>;
@@ -0,0 +1,243 @@
+use std::{fmt::Display, path::Path};
+
+use common::{Answer, SolveResult};
+
+use crate::cli::Part;
+
+/// Name of the expected answers file inside each `inputs/day_XX` folder.
+pub const FILE_NAME: &str = "answers.txt";
+
+/// Expected answers of the inputs of one day, read from `answers.txt`.
+///
+/// The file has one section per input file, holding the answer of each part:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// [test_input.txt]
+/// a: 24000
+/// b: 45000
+///
+/// [input.txt]
+/// a: 71124
+/// b:
+///     ##..##
+///     #..#..
+/// ```
+///
+/// An answer spanning several lines, like a bitmap, starts on the line after
+/// its key, each line indented; the indentation is dropped.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    entries: Vec<Expected>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Expected {
+    input: String,
+    part: Part,
+    answer: String,
+}
+
+/// Outcome of checking one part of one input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The solver failed, which never matches an expected answer.
+    Error(String),
+    /// No expected answer is recorded, the actual one is shown instead.
+    Unknown(String),
+}
+
+impl Answers {
+    /// Reads the answers of `dir`, empty if it has no answers file.
+    pub fn read(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => content
+                .parse()
+                .map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("failed to read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn get(&self, input: &str, part: Part) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.input == input && e.part == part)
+            .map(|e| e.answer.as_str())
+    }
+
+    pub fn check(&self, input: &str, part: Part, result: SolveResult<Answer>) -> Verdict {
+        let actual = match result {
+            Ok(answer) => answer.to_string(),
+            Err(e) => return Verdict::Error(e.to_string()),
+        };
+        match self.get(input, part) {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+                actual,
+            },
+            None => Verdict::Unknown(actual),
+        }
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries: Vec<Expected> = Vec::new();
+        let mut input = None;
+        // Whether the last entry is waiting for its indented lines.
+        let mut multiline = false;
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| format!("line {}: {reason}", i + 1);
+
+            if multiline && line.starts_with([' ', '\t']) {
+                let last = entries.last_mut().expect("multiline answer has an entry");
+                if !last.answer.is_empty() {
+                    last.answer.push('\n');
+                }
+                last.answer.push_str(line.trim());
+                continue;
+            }
+            if entries.last().is_some_and(|e| e.answer.is_empty()) {
+                return Err(error("missing answer"));
+            }
+            multiline = false;
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| error("unclosed section"))?;
+                input = Some(name.trim().to_string());
+                continue;
+            }
+
+            let input = input
+                .clone()
+                .ok_or_else(|| error("answer outside of a section"))?;
+            let (part, answer) = line
+                .split_once(':')
+                .ok_or_else(|| error("expected `<part>: <answer>`"))?;
+            let part = match part.trim() {
+                "a" => Part::A,
+                "b" => Part::B,
+                part => return Err(error(&format!("invalid part: {part}"))),
+            };
+            if entries.iter().any(|e| e.input == input && e.part == part) {
+                return Err(error("duplicate answer"));
+            }
+            let answer = answer.trim().to_string();
+            multiline = answer.is_empty();
+            entries.push(Expected {
+                input,
+                part,
+                answer,
+            });
+        }
+
+        if entries.last().is_some_and(|e| e.answer.is_empty()) {
+            return Err("missing answer at end of file".to_string());
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected, actual } => {
+                write!(
+                    f,
+                    "FAIL\n  expected:\n{}\n  actual:\n{}",
+                    indent(expected),
+                    indent(actual)
+                )
+            }
+            Self::Error(e) => write!(f, "FAIL\n  error: {e}"),
+            Self::Unknown(actual) if actual.contains('\n') => {
+                write!(f, "unknown\n{}", indent(actual))
+            }
+            Self::Unknown(actual) => write!(f, "unknown ({actual})"),
+        }
+    }
+}
+
+fn indent(s: &str) -> String {
+    s.lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "# day 10
+[test_input.txt]
+a: 13140
+
+[input.txt]
+a : 14220
+b:
+    ##.
+    .##
+";
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        assert_eq!(answers.get("test_input.txt", Part::A), Some("13140"));
+        assert_eq!(answers.get("test_input.txt", Part::B), None);
+        assert_eq!(answers.get("input.txt", Part::A), Some("14220"));
+        assert_eq!(answers.get("input.txt", Part::B), Some("##.\n.##"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("a: 1".parse::<Answers>().is_err());
+        assert!("[input.txt\na: 1".parse::<Answers>().is_err());
+        assert!("[input.txt]\nc: 1".parse::<Answers>().is_err());
+        assert!("[input.txt]\na: 1\na: 2".parse::<Answers>().is_err());
+        assert!("[input.txt]\na:\nb: 2".parse::<Answers>().is_err());
+        assert!("[input.txt]\nb:".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        assert_eq!(
+            answers.check("input.txt", Part::A, Ok(14220.into())),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.check("input.txt", Part::A, Ok(1.into())),
+            Verdict::Fail {
+                expected: "14220".to_string(),
+                actual: "1".to_string(),
+            }
+        );
+        assert_eq!(
+            answers.check("test_input.txt", Part::B, Ok("x".into())),
+            Verdict::Unknown("x".to_string())
+        );
+        assert_eq!(
+            answers.check("input.txt", Part::B, Err("no marker".into())),
+            Verdict::Error("no marker".to_string())
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use common::{Entry, SolveResult};
+
+/// Wall time samples of one stage of a day.
+pub struct Timings {
+    samples: Vec<Duration>,
+}
+
+pub struct DayBench {
+    pub day: u8,
+    pub title: &'static str,
+    pub parse: Timings,
+    pub a: Timings,
+    pub b: Timings,
+}
+
+impl Timings {
+    fn measure<T>(runs: usize, mut f: impl FnMut() -> SolveResult<T>) -> SolveResult<Self> {
+        let mut samples = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let start = Instant::now();
+            let result = f();
+            samples.push(start.elapsed());
+            result?;
+        }
+        samples.sort_unstable();
+        Ok(Self { samples })
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let len = self.samples.len();
+        match len {
+            0 => Duration::ZERO,
+            _ if len % 2 == 1 => self.samples[len / 2],
+            _ => (self.samples[len / 2 - 1] + self.samples[len / 2]) / 2,
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.last().copied().unwrap_or_default()
+    }
+}
+
+impl DayBench {
+    fn stages(&self) -> [(&'static str, &Timings); 3] {
+        [("parse", &self.parse), ("a", &self.a), ("b", &self.b)]
+    }
+}
+
+/// Runs the parse step and both parts of `entry` `runs` times each.
+pub fn bench_day(entry: &Entry, input: &str, runs: usize) -> SolveResult<DayBench> {
+    let parse = Timings::measure(runs, || entry.parse(input))?;
+    let parsed = entry.parse(input)?;
+    let a = Timings::measure(runs, || entry.part_a(&parsed))?;
+    let b = Timings::measure(runs, || entry.part_b(&parsed))?;
+
+    Ok(DayBench {
+        day: entry.day,
+        title: entry.title,
+        parse,
+        a,
+        b,
+    })
+}
+
+/// Human readable table of the results.
+pub fn format_table(benches: &[DayBench]) -> String {
+    let mut buf = format!(
+        "{:<4} {:<28} {:<6} {:>12} {:>12} {:>12}\n",
+        "day", "title", "stage", "min", "median", "max"
+    );
+    for bench in benches {
+        for (stage, timings) in bench.stages() {
+            let _ = writeln!(
+                buf,
+                "{:<4} {:<28} {:<6} {:>12} {:>12} {:>12}",
+                bench.day,
+                bench.title,
+                stage,
+                format!("{:.3?}", timings.min()),
+                format!("{:.3?}", timings.median()),
+                format!("{:.3?}", timings.max()),
+            );
+        }
+    }
+    buf
+}
+
+/// Tab separated report with one row per day and stage, times in nanoseconds.
+pub fn format_report(benches: &[DayBench]) -> String {
+    let mut buf = String::from("day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns\n");
+    for bench in benches {
+        for (stage, timings) in bench.stages() {
+            let _ = writeln!(
+                buf,
+                "{}\t{stage}\t{}\t{}\t{}\t{}",
+                bench.day,
+                timings.samples.len(),
+                timings.min().as_nanos(),
+                timings.median().as_nanos(),
+                timings.max().as_nanos(),
+            );
+        }
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(millis: &[u64]) -> Timings {
+        Timings {
+            samples: millis.iter().copied().map(Duration::from_millis).collect(),
+        }
+    }
+
+    #[test]
+    fn test_timings_stats() {
+        let odd = timings(&[1, 2, 9]);
+        assert_eq!(odd.min(), Duration::from_millis(1));
+        assert_eq!(odd.median(), Duration::from_millis(2));
+        assert_eq!(odd.max(), Duration::from_millis(9));
+
+        let even = timings(&[1, 2, 4, 9]);
+        assert_eq!(even.median(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_format_report() {
+        let bench = DayBench {
+            day: 6,
+            title: "Tuning Trouble",
+            parse: timings(&[1]),
+            a: timings(&[2, 4]),
+            b: timings(&[3]),
+        };
+        assert_eq!(
+            format_report(&[bench]),
+            "day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns\n\
+             6\tparse\t1\t1000000\t1000000\t1000000\n\
+             6\ta\t2\t2000000\t3000000\t4000000\n\
+             6\tb\t1\t3000000\t3000000\t3000000\n"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use common::{Answer, Bitmap};
+
+include!(concat!(env!("OUT_DIR"), "/build_hashes.rs"));
+
+/// Default folder of the cache, inside `target/` so `cargo clean` drops it.
+pub const DIR: &str = "target/answer_cache";
+
+/// Answers already computed, one file per day, part, build and input.
+///
+/// The build hash covers the sources of the day and of the libs, so editing
+/// a day makes its old answers unreachable; they are removed the next time
+/// the same part is stored.
+pub struct Cache {
+    dir: PathBuf,
+    build_hashes: Vec<(u8, u64)>,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self::with_build_hashes(dir, BUILD_HASHES.to_vec())
+    }
+
+    fn with_build_hashes(dir: impl Into<PathBuf>, build_hashes: Vec<(u8, u64)>) -> Self {
+        Self {
+            dir: dir.into(),
+            build_hashes,
+        }
+    }
+
+    /// The stored answer of `part` of `day` for `input`, if any.
+    pub fn get(&self, day: u8, part: char, input: &str) -> Option<Answer> {
+        let content = std::fs::read_to_string(self.path(day, part, input)?).ok()?;
+        decode(&content)
+    }
+
+    /// Stores `answer`, replacing those of the same part from other builds.
+    pub fn put(&self, day: u8, part: char, input: &str, answer: &Answer) -> Result<(), String> {
+        let Some(path) = self.path(day, part, input) else {
+            return Ok(());
+        };
+        let fail = |e: std::io::Error| format!("failed to cache {}: {e}", path.display());
+        std::fs::create_dir_all(&self.dir).map_err(fail)?;
+        self.remove_stale(day, part);
+        // Written aside then renamed, so a concurrent run never reads half of it.
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, encode(answer))
+            .and_then(|()| std::fs::rename(&tmp, &path))
+            .map_err(fail)
+    }
+
+    /// `None` for days built without a hash, like one created after the last
+    /// build of the runner.
+    fn path(&self, day: u8, part: char, input: &str) -> Option<PathBuf> {
+        let build = self.build_hash(day)?;
+        let input = fnv1a(input.as_bytes());
+        Some(self.dir.join(format!(
+            "{}{build:016x}_{input:016x}.txt",
+            prefix(day, part)
+        )))
+    }
+
+    fn build_hash(&self, day: u8) -> Option<u64> {
+        self.build_hashes
+            .iter()
+            .find(|&&(d, _)| d == day)
+            .map(|&(_, hash)| hash)
+    }
+
+    fn remove_stale(&self, day: u8, part: char) {
+        let (Some(build), Ok(entries)) = (self.build_hash(day), std::fs::read_dir(&self.dir))
+        else {
+            return;
+        };
+        let current = format!("{}{build:016x}_", prefix(day, part));
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            let name = file_name(&path);
+            if name.starts_with(&prefix(day, part)) && !name.starts_with(&current) {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+fn prefix(day: u8, part: char) -> String {
+    format!("day_{day:02}_{part}_")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The kind of the answer on the first line, then the answer as displayed.
+fn encode(answer: &Answer) -> String {
+    let kind = match answer {
+        Answer::Integer(_) => "integer",
+        Answer::Text(_) => "text",
+        Answer::Bitmap(_) => "bitmap",
+    };
+    format!("{kind}\n{answer}")
+}
+
+fn decode(content: &str) -> Option<Answer> {
+    let (kind, answer) = content.split_once('\n')?;
+    match kind {
+        "integer" => answer.parse::<i128>().ok().map(Answer::Integer),
+        "text" => Some(Answer::Text(answer.to_string())),
+        "bitmap" => {
+            let width = answer.lines().next()?.len();
+            let pixels = answer
+                .lines()
+                .flat_map(str::chars)
+                .map(|c| c == '#')
+                .collect::<Vec<_>>();
+            let rows_match = answer.lines().all(|row| row.len() == width);
+            (width > 0 && rows_match).then(|| Answer::Bitmap(Bitmap::new(width, pixels)))
+        }
+        _ => None,
+    }
+}
+
+/// 64 bit FNV-1a, stable across builds unlike the standard library hashers.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_cache_{}_{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let bitmap = Bitmap::new(3, vec![true, false, true, false, true, false]);
+        for answer in [
+            Answer::from(-42),
+            Answer::from("CMZ"),
+            Answer::from("two\nlines"),
+            Answer::from(bitmap),
+        ] {
+            assert_eq!(decode(&encode(&answer)), Some(answer));
+        }
+        assert_eq!(decode("integer\nx"), None);
+        assert_eq!(decode("bitmap\n##\n#"), None);
+    }
+
+    #[test]
+    fn test_get_put() {
+        let dir = temp_dir("get_put");
+        let cache = Cache::with_build_hashes(&dir, vec![(1, 7)]);
+        assert_eq!(cache.get(1, 'a', "1\n2\n"), None);
+        assert_eq!(cache.put(1, 'a', "1\n2\n", &3.into()), Ok(()));
+        assert_eq!(cache.get(1, 'a', "1\n2\n"), Some(3.into()));
+        assert_eq!(cache.get(1, 'b', "1\n2\n"), None);
+        assert_eq!(cache.get(1, 'a', "1\n3\n"), None);
+        // Days without a build hash are never cached.
+        assert_eq!(cache.put(2, 'a', "1\n2\n", &3.into()), Ok(()));
+        assert_eq!(cache.get(2, 'a', "1\n2\n"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rebuild_invalidates() {
+        let dir = temp_dir("rebuild");
+        let old = Cache::with_build_hashes(&dir, vec![(1, 7)]);
+        old.put(1, 'a', "1\n", &1.into()).unwrap();
+        old.put(1, 'b', "1\n", &1.into()).unwrap();
+
+        let new = Cache::with_build_hashes(&dir, vec![(1, 8)]);
+        assert_eq!(new.get(1, 'a', "1\n"), None);
+        new.put(1, 'a', "1\n", &2.into()).unwrap();
+        assert_eq!(new.get(1, 'a', "1\n"), Some(2.into()));
+        assert_eq!(old.get(1, 'a', "1\n"), None);
+        // Only the stored part is cleaned up.
+        assert_eq!(old.get(1, 'b', "1\n"), Some(1.into()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_every_day_has_a_build_hash() {
+        let cache = Cache::new(DIR);
+        for entry in crate::days::registry().iter() {
+            assert!(cache.build_hash(entry.day).is_some(), "day {}", entry.day);
+        }
+    }
+}
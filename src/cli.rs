@@ -0,0 +1,460 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage:
+    advent_of_code_2022_rust run <day> [a|b|both] [<input>|-] [--format <f>] [--cache]
+    advent_of_code_2022_rust run --all [a|b|both] [--format <f>] [--cache]
+    advent_of_code_2022_rust watch <day>
+    advent_of_code_2022_rust new-day <day> [<title>]
+    advent_of_code_2022_rust check [<day>...]
+    advent_of_code_2022_rust bench [<day>...] [--runs <n>] [--output <path>]
+    advent_of_code_2022_rust generate <day> [--size <n>] [--seed <s>]
+
+Arguments:
+    <day>      day number, e.g. 1 or 01
+    a|b|both   part to solve (default: both)
+    <input>    input file, `-` reads from stdin (default: inputs/day_XX/input.txt)
+
+Options:
+    --format <f>      text, json (one document) or jsonl (one line per part)
+                      (default: text)
+    --cache           reuse answers stored in target/answer_cache
+    --runs <n>        times each stage is run (default: 10)
+    --output <path>   tab separated report (default: bench_output.txt)
+    --size <n>        records in the generated input, e.g. elves, monkeys
+                      or trees (default: 1000)
+    --seed <s>        seed of the generated input (default: 0)";
+
+const DEFAULT_BENCH_RUNS: usize = 10;
+const DEFAULT_BENCH_OUTPUT: &str = "bench_output.txt";
+const DEFAULT_GENERATE_SIZE: usize = 1000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        day: u8,
+        part: Part,
+        input: Input,
+        format: Format,
+        cache: bool,
+    },
+    /// Solves every registered day on a thread pool.
+    RunAll {
+        part: Part,
+        format: Format,
+        cache: bool,
+    },
+    /// Re-runs a day whenever its sources or inputs change.
+    Watch {
+        day: u8,
+    },
+    /// Creates the crate and inputs of a new day from the template.
+    NewDay {
+        day: u8,
+        title: Option<String>,
+    },
+    /// Checks the given days, or every registered day when empty, against
+    /// their `answers.txt`.
+    Check {
+        days: Vec<u8>,
+    },
+    /// Benchmarks the given days, or every registered day when empty.
+    Bench {
+        days: Vec<u8>,
+        runs: usize,
+        output: PathBuf,
+    },
+    /// Writes a deterministic input of any size to stdout.
+    Generate {
+        day: u8,
+        size: usize,
+        seed: u64,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    A,
+    B,
+    Both,
+}
+
+/// How `run` prints its answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// One JSON document holding every part.
+    Json,
+    /// One JSON object per line and part.
+    JsonLines,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Part {
+    pub const fn includes_a(self) -> bool {
+        matches!(self, Self::A | Self::Both)
+    }
+
+    pub const fn includes_b(self) -> bool {
+        matches!(self, Self::B | Self::Both)
+    }
+}
+
+impl Input {
+    pub fn read(&self) -> Result<String, String> {
+        match self {
+            Self::Stdin => std::io::read_to_string(std::io::stdin())
+                .map_err(|e| format!("failed to read stdin: {e}")),
+            Self::File(path) => std::fs::read_to_string(path)
+                .map_err(|e| format!("failed to read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn default_for(day: u8) -> Self {
+        Self::File(Self::dir_for(day).join("input.txt"))
+    }
+
+    /// Folder holding the inputs of `day`.
+    pub fn dir_for(day: u8) -> PathBuf {
+        format!("inputs/day_{day:02}").into()
+    }
+
+    /// Every input of `day`: the `*.txt` files of its folder except the
+    /// expected answers, sorted by name.
+    pub fn all_for(day: u8) -> Result<Vec<PathBuf>, String> {
+        let dir = Self::dir_for(day);
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| format!("failed to read {}: {e}", dir.display()))?
+                .path();
+            let is_input = path.extension().is_some_and(|ext| ext == "txt")
+                && !path.ends_with(crate::answers::FILE_NAME);
+            if is_input && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+impl TryFrom<&str> for Part {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "a" | "A" => Ok(Self::A),
+            "b" | "B" => Ok(Self::B),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("invalid part: {s}")),
+        }
+    }
+}
+
+impl TryFrom<&str> for Format {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("invalid format: {s}")),
+        }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(s: &str) -> Self {
+        if s == "-" {
+            Self::Stdin
+        } else {
+            Self::File(s.into())
+        }
+    }
+}
+
+pub fn parse_args<I, S>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let args = args.into_iter().collect::<Vec<_>>();
+    let mut args = args.iter().map(AsRef::as_ref);
+
+    match args.next() {
+        None | Some("help" | "-h" | "--help") => Ok(Command::Help),
+        Some("run") => {
+            let mut format = Format::Text;
+            let mut cache = false;
+            let mut positional = Vec::new();
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--format" => format = Format::try_from(option_value(arg, args.next())?)?,
+                    "--cache" => cache = true,
+                    _ => positional.push(arg),
+                }
+            }
+            let mut args = positional.into_iter();
+            let first = args.next().ok_or_else(|| "missing day".to_string())?;
+            if first == "--all" {
+                let part = args.next().map_or(Ok(Part::Both), Part::try_from)?;
+                if let Some(arg) = args.next() {
+                    return Err(format!("unexpected argument: {arg}"));
+                }
+                return Ok(Command::RunAll {
+                    part,
+                    format,
+                    cache,
+                });
+            }
+            let day = parse_day(first)?;
+            let part = args.next().map_or(Ok(Part::Both), Part::try_from)?;
+            let input = args
+                .next()
+                .map_or_else(|| Input::default_for(day), Input::from);
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument: {arg}"));
+            }
+            Ok(Command::Run {
+                day,
+                part,
+                input,
+                format,
+                cache,
+            })
+        }
+        Some("watch") => {
+            let day = parse_day(args.next().ok_or_else(|| "missing day".to_string())?)?;
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument: {arg}"));
+            }
+            Ok(Command::Watch { day })
+        }
+        Some("new-day") => {
+            let day = parse_day(args.next().ok_or_else(|| "missing day".to_string())?)?;
+            let title = args.next().map(ToString::to_string);
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument: {arg}"));
+            }
+            Ok(Command::NewDay { day, title })
+        }
+        Some("check") => {
+            let days = args.map(parse_day).collect::<Result<_, _>>()?;
+            Ok(Command::Check { days })
+        }
+        Some("bench") => {
+            let mut days = Vec::new();
+            let mut runs = DEFAULT_BENCH_RUNS;
+            let mut output = PathBuf::from(DEFAULT_BENCH_OUTPUT);
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--runs" => {
+                        let value = option_value(arg, args.next())?;
+                        runs = match value.parse() {
+                            Ok(runs) if runs > 0 => runs,
+                            _ => return Err(format!("invalid runs: {value}")),
+                        };
+                    }
+                    "--output" => output = option_value(arg, args.next())?.into(),
+                    day => days.push(parse_day(day)?),
+                }
+            }
+            Ok(Command::Bench { days, runs, output })
+        }
+        Some("generate") => {
+            let day = parse_day(args.next().ok_or_else(|| "missing day".to_string())?)?;
+            let mut size = DEFAULT_GENERATE_SIZE;
+            let mut seed = 0;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--size" => {
+                        let value = option_value(arg, args.next())?;
+                        size = value
+                            .parse()
+                            .map_err(|_| format!("invalid size: {value}"))?;
+                    }
+                    "--seed" => {
+                        let value = option_value(arg, args.next())?;
+                        seed = value
+                            .parse()
+                            .map_err(|_| format!("invalid seed: {value}"))?;
+                    }
+                    _ => return Err(format!("unexpected argument: {arg}")),
+                }
+            }
+            Ok(Command::Generate { day, size, seed })
+        }
+        Some(cmd) => Err(format!("unknown command: {cmd}")),
+    }
+}
+
+fn option_value<'a>(option: &str, value: Option<&'a str>) -> Result<&'a str, String> {
+    value.ok_or_else(|| format!("missing value for {option}"))
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    match s.parse() {
+        Ok(day) if day > 0 => Ok(day),
+        _ => Err(format!("invalid day: {s}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse_args(["run", "07", "b", "-"]),
+            Ok(Command::Run {
+                day: 7,
+                part: Part::B,
+                input: Input::Stdin,
+                format: Format::Text,
+                cache: false,
+            })
+        );
+        assert_eq!(
+            parse_args(["run", "3"]),
+            Ok(Command::Run {
+                day: 3,
+                part: Part::Both,
+                input: Input::File("inputs/day_03/input.txt".into()),
+                format: Format::Text,
+                cache: false,
+            })
+        );
+        assert_eq!(
+            parse_args(["run", "--format", "json", "10", "b", "-"]),
+            Ok(Command::Run {
+                day: 10,
+                part: Part::B,
+                input: Input::Stdin,
+                format: Format::Json,
+                cache: false,
+            })
+        );
+        assert_eq!(
+            parse_args(["run", "--all"]),
+            Ok(Command::RunAll {
+                part: Part::Both,
+                format: Format::Text,
+                cache: false,
+            })
+        );
+        assert_eq!(
+            parse_args(["run", "--all", "a", "--cache", "--format", "jsonl"]),
+            Ok(Command::RunAll {
+                part: Part::A,
+                format: Format::JsonLines,
+                cache: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(parse_args(["watch", "09"]), Ok(Command::Watch { day: 9 }));
+        assert!(parse_args(["watch"]).is_err());
+        assert!(parse_args(["watch", "9", "a"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_new_day() {
+        assert_eq!(
+            parse_args(["new-day", "15"]),
+            Ok(Command::NewDay {
+                day: 15,
+                title: None,
+            })
+        );
+        assert_eq!(
+            parse_args(["new-day", "15", "Beacon Exclusion Zone"]),
+            Ok(Command::NewDay {
+                day: 15,
+                title: Some("Beacon Exclusion Zone".to_string()),
+            })
+        );
+        assert!(parse_args(["new-day"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_check() {
+        assert_eq!(
+            parse_args(["check"]),
+            Ok(Command::Check { days: Vec::new() })
+        );
+        assert_eq!(
+            parse_args(["check", "1", "02"]),
+            Ok(Command::Check { days: vec![1, 2] })
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse_args(["bench"]),
+            Ok(Command::Bench {
+                days: Vec::new(),
+                runs: 10,
+                output: "bench_output.txt".into(),
+            })
+        );
+        assert_eq!(
+            parse_args(["bench", "11", "--runs", "3", "14", "--output", "out.tsv"]),
+            Ok(Command::Bench {
+                days: vec![11, 14],
+                runs: 3,
+                output: "out.tsv".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        assert_eq!(
+            parse_args(["generate", "8"]),
+            Ok(Command::Generate {
+                day: 8,
+                size: 1000,
+                seed: 0,
+            })
+        );
+        assert_eq!(
+            parse_args(["generate", "05", "--seed", "42", "--size", "1000000"]),
+            Ok(Command::Generate {
+                day: 5,
+                size: 1_000_000,
+                seed: 42,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_args(["run"]).is_err());
+        assert!(parse_args(["run", "0"]).is_err());
+        assert!(parse_args(["run", "1", "c"]).is_err());
+        assert!(parse_args(["run", "1", "a", "x.txt", "extra"]).is_err());
+        assert!(parse_args(["run", "--all", "a", "x.txt"]).is_err());
+        assert!(parse_args(["run", "1", "--format", "xml"]).is_err());
+        assert!(parse_args(["run", "1", "--format"]).is_err());
+        assert!(parse_args(["check", "x"]).is_err());
+        assert!(parse_args(["bench", "--runs", "0"]).is_err());
+        assert!(parse_args(["bench", "--output"]).is_err());
+        assert!(parse_args(["generate"]).is_err());
+        assert!(parse_args(["generate", "1", "--size", "-1"]).is_err());
+        assert!(parse_args(["generate", "1", "2"]).is_err());
+        assert!(parse_args(["fly"]).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use common::Registry;
+
+/// Returns every solved day. The runner and the tests iterate over it, so a
+/// new day only has to be registered here.
+pub fn registry() -> Registry {
+    Registry::new()
+        .with::<day_01::Day01>()
+        .with::<day_02::Day02>()
+        .with::<day_03::Day03>()
+        .with::<day_04::Day04>()
+        .with::<day_05::Day05>()
+        .with::<day_06::Day06>()
+        .with::<day_07::Day07>()
+        .with::<day_08::Day08>()
+        .with::<day_09::Day09>()
+        .with::<day_10::Day10>()
+        .with::<day_11::Day11>()
+        .with::<day_12::Day12>()
+        .with::<day_13::Day13>()
+        .with::<day_14::Day14>()
+}
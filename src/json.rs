@@ -0,0 +1,131 @@
+use std::{fmt::Write, time::Duration};
+
+use common::Answer;
+
+use crate::run_all::Outcome;
+
+/// Every outcome in one document, with the summed solve time and the wall
+/// time of the whole run:
+///
+/// ```json
+/// {"outcomes":[...],"total_ns":1200,"wall_ns":900}
+/// ```
+pub fn document(outcomes: &[Outcome], wall: Duration) -> String {
+    let total = outcomes.iter().map(|o| o.elapsed).sum::<Duration>();
+    let outcomes = outcomes.iter().map(outcome).collect::<Vec<_>>();
+    format!(
+        "{{\"outcomes\":[{}],\"total_ns\":{},\"wall_ns\":{}}}",
+        outcomes.join(","),
+        total.as_nanos(),
+        wall.as_nanos(),
+    )
+}
+
+/// One outcome per line.
+pub fn lines(outcomes: &[Outcome]) -> String {
+    outcomes.iter().fold(String::new(), |mut buf, o| {
+        let _ = writeln!(buf, "{}", outcome(o));
+        buf
+    })
+}
+
+/// An outcome as an object. The answer is a number, a string or, for
+/// `grid` answers, an array of rows drawn with `#` and `.`; both `type` and
+/// `answer` are null when the part failed.
+///
+/// ```json
+/// {"day":1,"part":"a","type":"number","answer":24000,"elapsed_ns":1500,"cached":false,"error":null}
+/// ```
+pub fn outcome(outcome: &Outcome) -> String {
+    let (kind, answer, error) = match &outcome.answer {
+        Ok(Answer::Integer(n)) => ("\"number\"", n.to_string(), None),
+        Ok(Answer::Text(s)) => ("\"text\"", string(s), None),
+        Ok(Answer::Bitmap(b)) => {
+            let rows = b.to_string().lines().map(string).collect::<Vec<_>>();
+            ("\"grid\"", format!("[{}]", rows.join(",")), None)
+        }
+        Err(e) => ("null", "null".to_string(), Some(e.to_string())),
+    };
+    format!(
+        "{{\"day\":{},\"part\":\"{}\",\"type\":{kind},\"answer\":{answer},\"elapsed_ns\":{},\"cached\":{},\"error\":{}}}",
+        outcome.day,
+        outcome.part,
+        outcome.elapsed.as_nanos(),
+        outcome.cached,
+        error.as_deref().map_or_else(|| "null".to_string(), string),
+    )
+}
+
+/// `s` as a quoted JSON string.
+fn string(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(buf, "\\u{:04x}", u32::from(c));
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Bitmap;
+
+    use super::*;
+
+    fn outcome_of(answer: Result<Answer, &str>) -> Outcome {
+        Outcome {
+            day: 10,
+            part: 'b',
+            answer: answer.map_err(Into::into),
+            elapsed: Duration::from_micros(3),
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_string() {
+        assert_eq!(string("CMZ"), r#""CMZ""#);
+        assert_eq!(string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(
+            outcome(&outcome_of(Ok(13140.into()))),
+            r#"{"day":10,"part":"b","type":"number","answer":13140,"elapsed_ns":3000,"cached":false,"error":null}"#
+        );
+        assert_eq!(
+            outcome(&outcome_of(Ok("MCD".into()))),
+            r#"{"day":10,"part":"b","type":"text","answer":"MCD","elapsed_ns":3000,"cached":false,"error":null}"#
+        );
+        let grid = Bitmap::new(2, vec![true, false, false, true]);
+        assert_eq!(
+            outcome(&outcome_of(Ok(grid.into()))),
+            r##"{"day":10,"part":"b","type":"grid","answer":["#.",".#"],"elapsed_ns":3000,"cached":false,"error":null}"##
+        );
+        assert_eq!(
+            outcome(&outcome_of(Err("no \"noop\""))),
+            r#"{"day":10,"part":"b","type":null,"answer":null,"elapsed_ns":3000,"cached":false,"error":"no \"noop\""}"#
+        );
+    }
+
+    #[test]
+    fn test_document_and_lines() {
+        let outcomes = [outcome_of(Ok(1.into())), outcome_of(Ok(2.into()))];
+        let document = document(&outcomes, Duration::from_micros(4));
+        assert!(document.starts_with(r#"{"outcomes":[{"day":10,"#));
+        assert!(document.ends_with(r#""error":null}],"total_ns":6000,"wall_ns":4000}"#));
+        assert_eq!(lines(&outcomes).lines().count(), 2);
+    }
+}
@@ -1,45 +1,271 @@
+mod answers;
+mod bench;
+mod cache;
+mod cli;
+mod days;
+mod json;
+mod run_all;
+mod scaffold;
+mod watch;
+
+use std::{
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use answers::{Answers, Verdict};
+use cache::Cache;
+use cli::{Command, Format, Part};
+use common::{Answer, Entry, Registry, SolveResult};
+use run_all::Outcome;
+
 fn main() {
-    let grid = day_09::get_grid::<9>(include_str!("../inputs/day_09/input.txt"));
-    let visualization = grid.get_visualization();
-    std::fs::write("test_visualization_3.txt", visualization).expect("failed to write file");
+    let command = cli::parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("error: {e}\n\n{}", cli::USAGE);
+        std::process::exit(2);
+    });
+
+    let result = match command {
+        Command::Run {
+            day,
+            part,
+            input,
+            format,
+            cache,
+        } => run(day, part, &input, format, cache),
+        Command::RunAll {
+            part,
+            format,
+            cache,
+        } => run_all(part, format, cache),
+        Command::Watch { day } => watch(day),
+        Command::NewDay { day, title } => new_day(day, title.as_deref()),
+        Command::Check { days } => check(&days),
+        Command::Bench { days, runs, output } => bench(&days, runs, &output),
+        Command::Generate { day, size, seed } => generate(day, size, seed),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::path::{Path, PathBuf};
+fn run(day: u8, part: Part, input: &cli::Input, format: Format, cache: bool) -> Result<(), String> {
+    let registry = days::registry();
+    let entry = registry
+        .get(day)
+        .ok_or_else(|| format!("day {day} is not solved yet"))?;
+    let cache = cache.then(|| Cache::new(cache::DIR));
+    let start = Instant::now();
+    let outcomes = solve_timed(entry, part, input, cache.as_ref())?;
+    if format != Format::Text {
+        return report(&outcomes, start.elapsed(), format);
+    }
 
-    type DayFn = Box<dyn Fn(&str) -> String>;
+    for outcome in outcomes {
+        println!("{}", outcome.answer.map_err(|e| e.to_string())?);
+    }
+    Ok(())
+}
 
-    struct Day {
-        a: DayFn,
-        b: Option<DayFn>,
+/// Solves a single part straight from stdin when the day can, so huge piped
+/// inputs are never held in memory.
+fn stream(entry: &Entry, part: Part, input: &cli::Input) -> Option<SolveResult<Answer>> {
+    if !matches!(input, cli::Input::Stdin) {
+        return None;
+    }
+    let mut stdin = std::io::stdin().lock();
+    match part {
+        Part::A => entry.stream_a(&mut stdin),
+        Part::B => entry.stream_b(&mut stdin),
+        Part::Both => None,
     }
+}
 
-    impl<F: Fn(&str) -> String + 'static> From<F> for Day {
-        fn from(a: F) -> Self {
-            Self {
-                a: Box::new(a),
-                b: None,
-            }
+/// Solves `part` of `entry`, timing each part. Streamed inputs bypass the
+/// cache, they are never held whole to be hashed.
+fn solve_timed(
+    entry: &Entry,
+    part: Part,
+    input: &cli::Input,
+    cache: Option<&Cache>,
+) -> Result<Vec<Outcome>, String> {
+    let start = Instant::now();
+    if let Some(answer) = stream(entry, part, input) {
+        return Ok(vec![Outcome {
+            day: entry.day,
+            part: if part == Part::A { 'a' } else { 'b' },
+            answer,
+            elapsed: start.elapsed(),
+            cached: false,
+        }]);
+    }
+    Ok(run_all::run_all(&[(entry, input.read()?)], part, cache))
+}
+
+/// Prints `outcomes` in `format`, failing if any part did.
+fn report(outcomes: &[Outcome], wall: Duration, format: Format) -> Result<(), String> {
+    match format {
+        Format::Text => print!("{}", run_all::format_table(outcomes, wall)),
+        Format::Json => println!("{}", json::document(outcomes, wall)),
+        Format::JsonLines => print!("{}", json::lines(outcomes)),
+    }
+
+    let failed = outcomes.iter().filter(|o| o.answer.is_err()).count();
+    if failed > 0 {
+        return Err(format!("{failed} parts failed"));
+    }
+    Ok(())
+}
+
+fn watch(day: u8) -> Result<(), String> {
+    if days::registry().get(day).is_none() {
+        return Err(format!("day {day} is not solved yet"));
+    }
+    watch::watch(day)
+}
+
+fn new_day(day: u8, title: Option<&str>) -> Result<(), String> {
+    scaffold::new_day(Path::new("."), day, title)?;
+    println!("created days/day_{day:02} and inputs/day_{day:02}, registered in src/days.rs");
+    Ok(())
+}
+
+fn run_all(part: Part, format: Format, cache: bool) -> Result<(), String> {
+    let registry = days::registry();
+    let mut days = Vec::with_capacity(registry.iter().len());
+    for entry in registry.iter() {
+        let input = cli::Input::default_for(entry.day).read()?;
+        if input.is_empty() {
+            eprintln!("skipping day {}: empty input", entry.day);
+            continue;
         }
+        days.push((entry, input));
     }
 
-    impl<F1, F2> From<(F1, F2)> for Day
-    where
-        F1: Fn(&str) -> String + 'static,
-        F2: Fn(&str) -> String + 'static,
-    {
-        fn from((a, b): (F1, F2)) -> Self {
-            Self {
-                a: Box::new(a),
-                b: Some(Box::new(b)),
+    let start = Instant::now();
+    let cache = cache.then(|| Cache::new(cache::DIR));
+    let outcomes = run_all::run_all(&days, part, cache.as_ref());
+    report(&outcomes, start.elapsed(), format)
+}
+
+/// Entries of `days`, or every registered entry when `days` is empty.
+fn select<'a>(registry: &'a Registry, days: &[u8]) -> Result<Vec<&'a Entry>, String> {
+    if days.is_empty() {
+        return Ok(registry.iter().collect());
+    }
+    days.iter()
+        .map(|&day| {
+            registry
+                .get(day)
+                .ok_or_else(|| format!("day {day} is not solved yet"))
+        })
+        .collect()
+}
+
+fn check(days: &[u8]) -> Result<(), String> {
+    let registry = days::registry();
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    for entry in select(&registry, days)? {
+        let answers = Answers::read(&cli::Input::dir_for(entry.day))?;
+        for path in cli::Input::all_for(entry.day)? {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let input = cli::Input::File(path).read()?;
+            if input.is_empty() {
+                continue;
             }
+            let parsed = entry.parse(&input);
+            for (part, label) in [(Part::A, 'a'), (Part::B, 'b')] {
+                let result = parsed
+                    .as_ref()
+                    .map_err(Clone::clone)
+                    .and_then(|parsed| match part {
+                        Part::A => entry.part_a(parsed),
+                        _ => entry.part_b(parsed),
+                    });
+                let verdict = answers.check(&name, part, result);
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    Verdict::Unknown(_) => unknown += 1,
+                    _ => failed += 1,
+                }
+                println!("day {:02} {name:<16} {label}: {verdict}", entry.day);
+            }
+        }
+    }
+
+    println!("\n{passed} passed, {failed} failed, {unknown} unknown");
+    if failed > 0 {
+        return Err(format!("{failed} answers do not match"));
+    }
+    Ok(())
+}
+
+fn bench(days: &[u8], runs: usize, output: &Path) -> Result<(), String> {
+    let registry = days::registry();
+    let entries = select(&registry, days)?;
+
+    let mut benches = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let input = cli::Input::default_for(entry.day).read()?;
+        if input.is_empty() {
+            eprintln!("skipping day {}: empty input", entry.day);
+            continue;
+        }
+        benches.push(bench::bench_day(entry, &input, runs).map_err(|e| e.to_string())?);
+    }
+
+    print!("{}", bench::format_table(&benches));
+    std::fs::write(output, bench::format_report(&benches))
+        .map_err(|e| format!("failed to write {}: {e}", output.display()))
+}
+
+fn generate(day: u8, size: usize, seed: u64) -> Result<(), String> {
+    let generator =
+        generate::generator(day).ok_or_else(|| format!("day {day} has no generator"))?;
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    generator(&mut generate::Rng::new(seed), size, &mut out)
+        .and_then(|()| out.flush())
+        .map_err(|e| format!("failed to write the input: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use common::{Answer, Entry, SolveResult};
+
+    use crate::{cli::Input, days::registry};
+
+    /// Snapshot suffix of an input file: `input.txt` keeps no suffix,
+    /// `test_input.txt` becomes `_test` and `test_input_2.txt` `_test_2`.
+    fn snapshot_suffix(path: &Path) -> String {
+        let stem = path
+            .file_stem()
+            .expect("input must have a file name")
+            .to_string_lossy();
+        if stem == "input" {
+            return String::new();
+        }
+        match stem.strip_prefix("test_input") {
+            Some(rest) => format!("_test{rest}"),
+            None => format!("_{stem}"),
         }
     }
 
     fn test_day_input<F, P>(f: F, path: P, extra: &str)
     where
-        F: Fn(&str) -> String,
+        F: Fn(&str) -> SolveResult<Answer>,
         P: AsRef<Path>,
     {
         let path = path.as_ref();
@@ -50,71 +276,152 @@ mod tests {
             .expect("test case parent folder must have a file stem")
             .to_string_lossy()
             .to_string();
-        let suffix = if path.ends_with("test_input.txt") {
-            "_test"
-        } else {
-            ""
-        };
         let input = std::fs::read_to_string(path).expect("failed to read input");
         if input.is_empty() {
             return;
         }
 
         let mut settings = insta::Settings::new();
-        settings.set_snapshot_suffix(folder + extra + suffix);
+        settings.set_snapshot_suffix(folder + extra + &snapshot_suffix(path));
 
         settings.bind(|| {
-            let result = f(&input);
+            let result = f(&input).unwrap_or_else(|e| panic!("{e}")).to_string();
             insta::assert_snapshot!(result);
         });
     }
 
-    fn test_day(d: Day, day: usize) {
-        let path: PathBuf = format!("inputs/day_{day:02}").into();
-        test_day_input(&d.a, path.join("test_input.txt"), "a");
-        test_day_input(&d.a, path.join("input.txt"), "a");
+    fn test_day(entry: &Entry) {
+        let paths = Input::all_for(entry.day).expect("failed to list inputs");
+        let a = |input: &str| entry.solve_a(input);
+        let b = |input: &str| entry.solve_b(input);
+        for path in &paths {
+            test_day_input(a, path, "a");
+        }
+        for path in &paths {
+            test_day_input(b, path, "b");
+        }
+        for path in &paths {
+            test_day_stream(entry, path);
+        }
+    }
+
+    /// Streaming days must agree with their whole input counterpart.
+    fn test_day_stream(entry: &Entry, path: &Path) {
+        let input = std::fs::read_to_string(path).expect("failed to read input");
+        if let Some(answer) = entry.stream_a(&mut input.as_bytes()) {
+            assert_eq!(answer, entry.solve_a(&input), "{}", path.display());
+        }
+        if let Some(answer) = entry.stream_b(&mut input.as_bytes()) {
+            assert_eq!(answer, entry.solve_b(&input), "{}", path.display());
+        }
+    }
 
-        if let Some(b) = d.b {
-            test_day_input(&b, path.join("test_input.txt"), "b");
-            test_day_input(&b, path.join("input.txt"), "b");
+    #[test]
+    fn test_snapshot_suffix() {
+        assert_eq!(snapshot_suffix(Path::new("day_06/input.txt")), "");
+        assert_eq!(snapshot_suffix(Path::new("day_06/test_input.txt")), "_test");
+        assert_eq!(
+            snapshot_suffix(Path::new("day_06/test_input_2.txt")),
+            "_test_2"
+        );
+        assert_eq!(snapshot_suffix(Path::new("day_09/larger.txt")), "_larger");
+    }
+
+    #[test]
+    fn test_generated_inputs() {
+        let registry = registry();
+        // Days fresh from `new-day` have no generator yet.
+        let entries = registry
+            .iter()
+            .filter(|entry| generate::generator(entry.day).is_some());
+        for entry in entries {
+            for seed in 0..3 {
+                let input = generate::to_string(entry.day, 30, seed).expect("has a generator");
+                let parsed = entry
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("{e}\n{input}"));
+                let mut answers = vec![('a', entry.part_a(&parsed))];
+                // Part b of day 11 replays the whole history of every item,
+                // which takes seconds even on tiny inputs.
+                if entry.day != 11 {
+                    answers.push(('b', entry.part_b(&parsed)));
+                }
+                for (part, answer) in answers {
+                    if let Err(e) = answer {
+                        panic!("{part} of seed {seed}: {e}\n{input}");
+                    }
+                }
+            }
         }
     }
 
-    fn get_days() -> Vec<Day> {
-        [
-            (day_01::calc_a, day_01::calc_b).into(),
-            (day_02::calc_a, day_02::calc_b).into(),
-            (day_03::calc_a, day_03::calc_b).into(),
-            (day_04::calc_a, day_04::calc_b).into(),
-            (day_05::calc_a, day_05::calc_b).into(),
-            (day_06::calc_a, day_06::calc_b).into(),
-            (day_07::calc_a, day_07::calc_b).into(),
-            (day_08::calc_a, day_08::calc_b).into(),
-            (day_09::calc_a, day_09::calc_b).into(),
-            (day_10::calc_a, day_10::calc_b).into(),
-            (day_11::calc_a, day_11::calc_b).into(),
-            (day_12::calc_a, day_12::calc_b).into(),
-            (day_13::calc_a, day_13::calc_b).into(),
-            (day_14::calc_a, day_14::calc_b).into(),
-        ]
-        .into()
+    /// Parsers must reject malformed input with an error, not a panic. The
+    /// fuzz targets under `fuzz/` explore further, this replays every
+    /// example with each of its lines cut short.
+    #[test]
+    fn test_truncated_lines() {
+        for entry in registry().iter() {
+            let paths = Input::all_for(entry.day).expect("failed to list inputs");
+            for path in paths
+                .iter()
+                .filter(|p| snapshot_suffix(p).starts_with("_test"))
+            {
+                let input = std::fs::read_to_string(path).expect("failed to read input");
+                let lines = input.lines().collect::<Vec<_>>();
+                for (i, line) in lines.iter().enumerate() {
+                    for (cut, _) in line.char_indices() {
+                        let mut truncated = lines.clone();
+                        truncated[i] = &line[..cut];
+                        let _ = entry.parse(&truncated.join("\n"));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inputs saved by a Windows editor, with a BOM, CRLF line endings and
+    /// trailing blank lines, must give the same answers.
+    #[test]
+    fn test_windows_inputs() {
+        for entry in registry().iter() {
+            let paths = Input::all_for(entry.day).expect("failed to list inputs");
+            for path in paths
+                .iter()
+                .filter(|p| snapshot_suffix(p).starts_with("_test"))
+            {
+                let input = std::fs::read_to_string(path).expect("failed to read input");
+                let windows = format!("\u{feff}{}\r\n\r\n", input.replace('\n', "\r\n"));
+                assert_eq!(
+                    entry.solve_a(&windows),
+                    entry.solve_a(&input),
+                    "{}",
+                    path.display()
+                );
+                assert_eq!(
+                    entry.solve_b(&windows),
+                    entry.solve_b(&input),
+                    "{}",
+                    path.display()
+                );
+            }
+        }
     }
 
     #[test]
     fn test_days_wo_last() {
-        let mut days = get_days();
-        days.pop(); // skip last day
+        let registry = registry();
+        let days = registry.iter();
+        let len = days.len();
 
-        for (day, functions) in days.into_iter().enumerate() {
-            test_day(functions, day + 1);
+        for entry in days.take(len - 1) {
+            test_day(entry);
         }
     }
 
     #[test]
     fn test_last_day() {
-        let mut days = get_days();
-        let len = days.len();
-        let day = days.pop().expect("no days");
-        test_day(day, len);
+        let registry = registry();
+        let entry = registry.last().expect("no days");
+        test_day(entry);
     }
 }
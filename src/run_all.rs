@@ -0,0 +1,262 @@
+use std::{
+    any::Any,
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use common::{Answer, Entry, SolveError, SolveResult};
+
+use crate::{cache::Cache, cli::Part};
+
+/// One solved part of one day.
+pub struct Outcome {
+    pub day: u8,
+    pub part: char,
+    pub answer: SolveResult<Answer>,
+    /// Time to parse the input and solve the part, or to read the answer
+    /// from the cache.
+    pub elapsed: Duration,
+    pub cached: bool,
+}
+
+struct Job<'a> {
+    entry: &'a Entry,
+    input: &'a str,
+    part: char,
+    cache: Option<&'a Cache>,
+}
+
+impl Job<'_> {
+    fn run(&self) -> Outcome {
+        let start = Instant::now();
+        let day = self.entry.day;
+        if let Some(answer) = self.cache.and_then(|c| c.get(day, self.part, self.input)) {
+            return Outcome {
+                day,
+                part: self.part,
+                answer: Ok(answer),
+                elapsed: start.elapsed(),
+                cached: true,
+            };
+        }
+        // A panicking day is a bug, reported as its answer rather than taking
+        // every other day down with it.
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| match self.part {
+            'a' => self.entry.solve_a(self.input),
+            _ => self.entry.solve_b(self.input),
+        }))
+        .unwrap_or_else(|payload| {
+            Err(SolveError::new(format!("panicked: {}", panic_message(&*payload))).for_day(day))
+        });
+        let elapsed = start.elapsed();
+        if let (Some(cache), Ok(answer)) = (self.cache, &answer) {
+            if let Err(e) = cache.put(day, self.part, self.input, answer) {
+                eprintln!("warning: {e}");
+            }
+        }
+        Outcome {
+            day,
+            part: self.part,
+            answer,
+            elapsed,
+            cached: false,
+        }
+    }
+}
+
+/// Message given to `panic!`, if any.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+/// Solves `part` of every `(entry, input)` pair on one thread per core.
+///
+/// Both parts of a day are separate jobs, so a slow part does not hold back
+/// the other. The outcomes keep the order of `days`. Answers are read from
+/// and stored in `cache` when given.
+pub fn run_all(days: &[(&Entry, String)], part: Part, cache: Option<&Cache>) -> Vec<Outcome> {
+    let mut jobs = Vec::new();
+    for (entry, input) in days {
+        for (label, included) in [('a', part.includes_a()), ('b', part.includes_b())] {
+            if included {
+                jobs.push(Job {
+                    entry,
+                    input,
+                    part: label,
+                    cache,
+                });
+            }
+        }
+    }
+
+    let workers = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(jobs.len());
+    let next = AtomicUsize::new(0);
+    let mut outcomes = thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(idx) else {
+                            return done;
+                        };
+                        done.push((idx, job.run()));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("worker panicked"))
+            .collect::<Vec<_>>()
+    });
+    outcomes.sort_by_key(|&(idx, _)| idx);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// Table of every outcome, followed by the summed solve time and the wall
+/// time of the whole run. Answers read from the cache show `cached` instead
+/// of their time.
+pub fn format_table(outcomes: &[Outcome], wall: Duration) -> String {
+    let mut buf = format!(
+        "{:<4} {:<4} {:<40} {:>12}\n",
+        "day", "part", "answer", "time"
+    );
+    for outcome in outcomes {
+        let answer = match &outcome.answer {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("error: {e}"),
+        };
+        let mut lines = answer.lines();
+        let _ = writeln!(
+            buf,
+            "{:<4} {:<4} {:<40} {:>12}",
+            outcome.day,
+            outcome.part,
+            lines.next().unwrap_or_default(),
+            if outcome.cached {
+                "cached".to_string()
+            } else {
+                format!("{:.3?}", outcome.elapsed)
+            },
+        );
+        for line in lines {
+            let _ = writeln!(buf, "{:<9} {line}", "");
+        }
+    }
+    let total = outcomes.iter().map(|o| o.elapsed).sum::<Duration>();
+    let _ = writeln!(
+        buf,
+        "{:<50} {:>12}\n{:<50} {:>12}",
+        "total",
+        format!("{total:.3?}"),
+        "wall",
+        format!("{wall:.3?}"),
+    );
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Solution;
+
+    use super::*;
+    use crate::days::registry;
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        const DAY: u8 = 26;
+        const TITLE: &'static str = "Panicking";
+
+        type Input = ();
+
+        fn parse(_: &str) -> SolveResult<Self::Input> {
+            Ok(())
+        }
+
+        fn part_a((): &Self::Input) -> SolveResult<Answer> {
+            panic!("boom")
+        }
+
+        fn part_b((): &Self::Input) -> SolveResult<Answer> {
+            Ok(1.into())
+        }
+    }
+
+    #[test]
+    fn test_run_all_keeps_order() {
+        let registry = registry();
+        let days = registry
+            .iter()
+            .filter(|e| [1, 2, 6].contains(&e.day))
+            .map(|e| (e, "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()))
+            .collect::<Vec<_>>();
+        let outcomes = run_all(&days, Part::Both, None);
+        let order = outcomes.iter().map(|o| (o.day, o.part)).collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b'), (6, 'a'), (6, 'b')]
+        );
+        assert_eq!(outcomes[4].answer, Ok(7.into()));
+        assert_eq!(outcomes[5].answer, Ok(19.into()));
+        assert!(outcomes[0].answer.is_err());
+    }
+
+    #[test]
+    fn test_run_all_reports_panics() {
+        let entry = Entry::of::<Panicking>();
+        let outcomes = run_all(&[(&entry, String::new())], Part::Both, None);
+        assert_eq!(
+            outcomes[0].answer.as_ref().unwrap_err().to_string(),
+            "day 26: panicked: boom"
+        );
+        assert_eq!(outcomes[1].answer, Ok(1.into()));
+    }
+
+    #[test]
+    fn test_format_table() {
+        let outcomes = [
+            Outcome {
+                day: 10,
+                part: 'b',
+                answer: Ok("#.\n.#".into()),
+                elapsed: Duration::from_millis(2),
+                cached: false,
+            },
+            Outcome {
+                day: 11,
+                part: 'a',
+                answer: Err("day 11: no monkeys".into()),
+                elapsed: Duration::from_millis(1),
+                cached: false,
+            },
+        ];
+        let table = format_table(&outcomes, Duration::from_millis(2));
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            ["10", "b", "#.", "2.000ms"]
+        );
+        assert_eq!(lines[2].trim(), ".#");
+        assert!(lines[3].contains("error: day 11: no monkeys"));
+        assert_eq!(
+            lines[4].split_whitespace().collect::<Vec<_>>(),
+            ["total", "3.000ms"]
+        );
+        assert_eq!(
+            lines[5].split_whitespace().collect::<Vec<_>>(),
+            ["wall", "2.000ms"]
+        );
+    }
+}
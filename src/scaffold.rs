@@ -0,0 +1,170 @@
+use std::path::Path;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/day/Cargo.toml.tpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/day/lib.rs.tpl");
+const DEFAULT_TITLE: &str = "TODO";
+
+/// Creates the crate and inputs of `day` below `root` and registers it.
+///
+/// Every check runs before the first file is written, and an existing day
+/// is never overwritten.
+pub fn new_day(root: &Path, day: u8, title: Option<&str>) -> Result<(), String> {
+    let crate_dir = root.join(format!("days/day_{day:02}"));
+    let inputs_dir = root.join(format!("inputs/day_{day:02}"));
+    for dir in [&crate_dir, &inputs_dir] {
+        if dir.exists() {
+            return Err(format!("{} already exists", dir.display()));
+        }
+    }
+
+    let manifest_path = root.join("Cargo.toml");
+    let days_path = root.join("src/days.rs");
+    let manifest = add_dependency(&read(&manifest_path)?, day)?;
+    let days = register(&read(&days_path)?, day)?;
+
+    // Inserted as a literal, so quotes and backslashes in the title are escaped.
+    let title = format!("{:?}", title.unwrap_or(DEFAULT_TITLE));
+    let fill = |template: &str| {
+        template
+            .replace("{{day}}", &format!("{day:02}"))
+            .replace("{{number}}", &day.to_string())
+            .replace("{{title}}", &title)
+    };
+    write(&crate_dir.join("Cargo.toml"), &fill(CARGO_TEMPLATE))?;
+    write(&crate_dir.join("src/lib.rs"), &fill(LIB_TEMPLATE))?;
+    for name in ["input.txt", "test_input.txt"] {
+        let template = root.join("inputs/empty").join(name);
+        std::fs::create_dir_all(&inputs_dir)
+            .and_then(|()| std::fs::copy(&template, inputs_dir.join(name)))
+            .map_err(|e| format!("failed to copy {}: {e}", template.display()))?;
+    }
+    write(&manifest_path, &manifest)?;
+    write(&days_path, &days)
+}
+
+/// Adds the path dependency of `day` to the root manifest, after the last
+/// day before it.
+fn add_dependency(manifest: &str, day: u8) -> Result<String, String> {
+    let name = format!("day_{day:02}");
+    let line = format!("{name} = {{ path = \"days/{name}\" }}");
+    insert_sorted(manifest, &line, "day_", &name)
+        .ok_or_else(|| format!("{name} is already a dependency"))
+}
+
+/// Adds `day` to the registry in `src/days.rs`, after the last day before it.
+fn register(days: &str, day: u8) -> Result<String, String> {
+    let line = format!("        .with::<day_{day:02}::Day{day:02}>()");
+    insert_sorted(days, &line, ".with::<day_", line.trim())
+        .ok_or_else(|| format!("day {day} is already registered"))
+}
+
+/// Inserts `line` among the lines starting with `prefix`, keeping them sorted
+/// by comparing `key` with their trimmed content. `None` if a line with the
+/// same key exists.
+fn insert_sorted(content: &str, line: &str, prefix: &str, key: &str) -> Option<String> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut idx = None;
+    for (i, existing) in lines.iter().enumerate() {
+        let existing = existing.trim();
+        if !existing.starts_with(prefix) {
+            continue;
+        }
+        if existing.starts_with(key) {
+            return None;
+        }
+        if existing < key {
+            idx = Some(i + 1);
+        } else if idx.is_none() {
+            idx = Some(i);
+        }
+    }
+
+    let mut lines = lines;
+    lines.insert(idx.unwrap_or(lines.len()), line);
+    let mut out = lines.join("\n");
+    if content.ends_with('\n') {
+        out.push('\n');
+    }
+    Some(out)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(path, content))
+        .map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[dependencies]
+common = { path = \"libs/common\" }
+day_01 = { path = \"days/day_01\" }
+day_03 = { path = \"days/day_03\" }
+
+[dev-dependencies]
+";
+
+    const DAYS: &str = "pub fn registry() -> Registry {
+    Registry::new()
+        .with::<day_01::Day01>()
+        .with::<day_03::Day03>()
+}
+";
+
+    #[test]
+    fn test_add_dependency() {
+        let manifest = add_dependency(MANIFEST, 2).unwrap();
+        assert!(manifest.contains(
+            "day_01 = { path = \"days/day_01\" }\n\
+             day_02 = { path = \"days/day_02\" }\n\
+             day_03"
+        ));
+        let manifest = add_dependency(MANIFEST, 15).unwrap();
+        assert!(manifest.contains("day_03\" }\nday_15 = { path = \"days/day_15\" }\n\n"));
+        assert!(add_dependency(MANIFEST, 3).is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        let days = register(DAYS, 15).unwrap();
+        assert!(days
+            .ends_with("        .with::<day_03::Day03>()\n        .with::<day_15::Day15>()\n}\n"));
+        let days = register(DAYS, 2).unwrap();
+        assert!(days.contains("Day01>()\n        .with::<day_02::Day02>()\n"));
+        assert!(register(DAYS, 1).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc_new_day_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("inputs/empty")).unwrap();
+        std::fs::write(root.join("inputs/empty/input.txt"), "").unwrap();
+        std::fs::write(root.join("inputs/empty/test_input.txt"), "").unwrap();
+        std::fs::write(root.join("Cargo.toml"), MANIFEST).unwrap();
+        std::fs::write(root.join("src/days.rs"), DAYS).unwrap();
+
+        new_day(&root, 15, Some("Beacon Exclusion Zone")).unwrap();
+        let lib = std::fs::read_to_string(root.join("days/day_15/src/lib.rs")).unwrap();
+        assert!(lib.contains("impl Solution for Day15"));
+        assert!(lib.contains("const DAY: u8 = 15;"));
+        assert!(lib.contains("\"Beacon Exclusion Zone\""));
+        assert!(root.join("inputs/day_15/test_input.txt").is_file());
+
+        new_day(&root, 16, Some(r#"Say "hi" \ bye"#)).unwrap();
+        let lib = std::fs::read_to_string(root.join("days/day_16/src/lib.rs")).unwrap();
+        assert!(lib.contains(r#"const TITLE: &'static str = "Say \"hi\" \\ bye";"#));
+        assert!(new_day(&root, 15, None)
+            .unwrap_err()
+            .contains("already exists"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
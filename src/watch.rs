@@ -0,0 +1,143 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time of every file below some folders.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    files: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl Snapshot {
+    /// Walks `dirs` recursively; missing folders are treated as empty.
+    pub fn take(dirs: &[PathBuf]) -> Self {
+        let mut files = BTreeMap::new();
+        for dir in dirs {
+            walk(dir, &mut files);
+        }
+        Self { files }
+    }
+}
+
+fn walk(dir: &Path, files: &mut BTreeMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            walk(&path, files);
+        } else if let Ok(modified) = metadata.modified() {
+            files.insert(path, modified);
+        }
+    }
+}
+
+/// Rebuilds and re-runs both parts of `day` whenever its sources or inputs
+/// change, printing how the answers changed since the previous run.
+///
+/// The solvers are compiled into this binary, so every run goes through
+/// `cargo run` to pick up the edited code.
+pub fn watch(day: u8) -> ! {
+    let dirs = [
+        PathBuf::from(format!("days/day_{day:02}/src")),
+        crate::cli::Input::dir_for(day),
+    ];
+    let mut previous: [Option<Result<String, String>>; 2] = [None, None];
+    let mut snapshot = None;
+
+    loop {
+        let current = Some(Snapshot::take(&dirs));
+        if snapshot != current {
+            snapshot = current;
+            println!("[watch] running day {day}");
+            for (part, previous) in ["a", "b"].into_iter().zip(&mut previous) {
+                let result = run_part(day, part);
+                println!("{}", describe(part, previous.as_ref(), &result));
+                *previous = Some(result);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn run_part(day: u8, part: &str) -> Result<String, String> {
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--release", "--quiet", "--", "run"])
+        .arg(day.to_string())
+        .arg(part)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("failed to run cargo: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string())
+    } else {
+        Err(format!("failed ({})", output.status))
+    }
+}
+
+/// One report line (or block, for multi-line answers) of a part.
+fn describe(
+    part: &str,
+    previous: Option<&Result<String, String>>,
+    current: &Result<String, String>,
+) -> String {
+    let show = |result: &Result<String, String>| match result {
+        Ok(answer) if answer.contains('\n') => format!("\n{answer}"),
+        Ok(answer) => answer.clone(),
+        Err(e) => e.clone(),
+    };
+    match previous {
+        None => format!("{part}: {}", show(current)),
+        Some(previous) if previous == current => format!("{part}: {} (unchanged)", show(current)),
+        Some(previous) => format!("{part}: {} -> {}", show(previous), show(current)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let old = Ok("13".to_string());
+        let new = Ok("36".to_string());
+        assert_eq!(describe("a", None, &new), "a: 36");
+        assert_eq!(describe("a", Some(&new), &new), "a: 36 (unchanged)");
+        assert_eq!(describe("b", Some(&old), &new), "b: 13 -> 36");
+        assert_eq!(
+            describe("b", Some(&new), &Err("failed".to_string())),
+            "b: 36 -> failed"
+        );
+        assert_eq!(
+            describe("b", None, &Ok("#.\n.#".to_string())),
+            "b: \n#.\n.#"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_changes() {
+        let dir = std::env::temp_dir().join(format!("aoc_watch_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let dirs = [dir.clone(), dir.join("missing")];
+
+        let empty = Snapshot::take(&dirs);
+        std::fs::write(dir.join("src/lib.rs"), "").unwrap();
+        let created = Snapshot::take(&dirs);
+        assert_ne!(empty, created);
+        assert_eq!(created, Snapshot::take(&dirs));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Snapshot::take(&dirs), Snapshot::default());
+    }
+}
@@ -0,0 +1,41 @@
+use common::{Answer, Solution, SolveResult};
+
+pub struct Day{{day}};
+
+impl Solution for Day{{day}} {
+    const DAY: u8 = {{number}};
+    const TITLE: &'static str = {{title}};
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> SolveResult<Self::Input> {
+        Ok(input.lines().map(ToString::to_string).collect())
+    }
+
+    fn part_a(_input: &Self::Input) -> SolveResult<Answer> {
+        Err("part a is not solved yet".into())
+    }
+
+    fn part_b(_input: &Self::Input) -> SolveResult<Answer> {
+        Err("part b is not solved yet".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "";
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part_a() {
+        assert_eq!(Day{{day}}::solve_a(TEST_INPUT), Ok(0.into()));
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part_b() {
+        assert_eq!(Day{{day}}::solve_b(TEST_INPUT), Ok(0.into()));
+    }
+}